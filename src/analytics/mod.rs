@@ -0,0 +1,471 @@
+use super::fantasy_client::{FantasyMatchup, FantasyTeam, PastMatchup, PastTeam, Schedule};
+use std::collections::HashMap;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Record {
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+}
+
+impl Record {
+    pub fn add(&mut self, score: f64, other: f64) {
+        if score > other {
+            self.wins += 1;
+        } else if score < other {
+            self.losses += 1;
+        } else {
+            self.ties += 1;
+        }
+    }
+
+    // ties count as half a win
+    pub fn win_total(&self) -> f64 {
+        self.wins as f64 + self.ties as f64 / 2.0
+    }
+
+    pub fn win_pct(&self) -> f64 {
+        self.win_total() / (self.wins + self.losses + self.ties).max(1) as f64
+    }
+
+    fn merge(&mut self, other: &Record) {
+        self.wins += other.wins;
+        self.losses += other.losses;
+        self.ties += other.ties;
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.ties > 0 {
+            write!(f, "{}-{}-{}", self.wins, self.losses, self.ties)
+        } else {
+            write!(f, "{}-{}", self.wins, self.losses)
+        }
+    }
+}
+
+pub struct TeamLuck {
+    pub team: FantasyTeam,
+    pub record: Record,
+    // as if every team played every other team every week
+    pub all_play: Record,
+    pub expected_wins: f64,
+    // actual wins minus expected wins; positive is lucky
+    pub luck: f64,
+    pub points_against: f64,
+    // 1 is the team that's had the most scored against it
+    pub points_against_rank: usize,
+}
+
+// Scores as (team, score) for each week, leaving out games that haven't been scored.
+fn weekly_scores(matchups: &[FantasyMatchup]) -> HashMap<u32, Vec<(&FantasyTeam, f64)>> {
+    let mut weeks: HashMap<u32, Vec<(&FantasyTeam, f64)>> = HashMap::new();
+    for m in matchups.iter() {
+        if let (Some(s1), Some(s2)) = (m.score1, m.score2) {
+            let week = weeks.entry(m.week_num).or_default();
+            week.push((&m.team1, s1));
+            week.push((&m.team2, s2));
+        }
+    }
+    weeks
+}
+
+// Each team's all-play record for each week it played, along with how many opponents that was.
+fn weekly_all_play(matchups: &[FantasyMatchup]) -> Vec<(&FantasyTeam, Record, usize)> {
+    let mut records = vec![];
+    for scores in weekly_scores(matchups).values() {
+        for (i, (team, score)) in scores.iter().enumerate() {
+            let mut week = Record::default();
+            for (j, (_, other)) in scores.iter().enumerate() {
+                if i != j {
+                    week.add(*score, *other);
+                }
+            }
+            records.push((*team, week, scores.len() - 1));
+        }
+    }
+    records
+}
+
+fn entry<'a>(teams: &'a mut HashMap<String, TeamLuck>, team: &FantasyTeam) -> &'a mut TeamLuck {
+    teams.entry(team.id.clone()).or_insert_with(|| TeamLuck {
+        team: team.clone(),
+        record: Record::default(),
+        all_play: Record::default(),
+        expected_wins: 0.0,
+        luck: 0.0,
+        points_against: 0.0,
+        points_against_rank: 0,
+    })
+}
+
+// Luck numbers for every team from a season's worth of finished matchups, luckiest first.
+pub fn luck(matchups: &[FantasyMatchup]) -> Vec<TeamLuck> {
+    let mut teams: HashMap<String, TeamLuck> = HashMap::new();
+    for m in matchups.iter() {
+        if let (Some(s1), Some(s2)) = (m.score1, m.score2) {
+            let one = entry(&mut teams, &m.team1);
+            one.record.add(s1, s2);
+            one.points_against += s2;
+            let two = entry(&mut teams, &m.team2);
+            two.record.add(s2, s1);
+            two.points_against += s1;
+        }
+    }
+
+    for (team, week, opponents) in weekly_all_play(matchups) {
+        let t = entry(&mut teams, team);
+        t.all_play.merge(&week);
+        t.expected_wins += week.win_total() / opponents.max(1) as f64;
+    }
+
+    let mut luck: Vec<TeamLuck> = teams.into_values().collect();
+    luck.sort_by(|a, b| b.points_against.partial_cmp(&a.points_against).unwrap());
+    for (i, t) in luck.iter_mut().enumerate() {
+        t.points_against_rank = i + 1;
+        t.luck = t.record.win_total() - t.expected_wins;
+    }
+    luck.sort_by(|a, b| b.luck.partial_cmp(&a.luck).unwrap());
+    luck
+}
+
+// How tough a set of opponents is, going by how they've done against the whole league.
+pub struct Strength {
+    pub games: u32,
+    // opponents' average points per game
+    pub opponent_points: f64,
+    // opponents' all-play win rate, so .500 is an average schedule
+    pub opponent_all_play: f64,
+}
+
+pub struct TeamSchedule {
+    pub team: FantasyTeam,
+    // None when there are no games on that side of the schedule
+    pub past: Option<Strength>,
+    pub remaining: Option<Strength>,
+}
+
+// Until this many weeks of a season have been played, opponents are also rated on the end of
+// last season's regular season, a week of it for each week still to come.
+const PRIOR_WEEKS: usize = 4;
+
+// The last `weeks` regular season weeks before `season` in the league's history, as games
+// between this season's teams, going by owner.  Owners who've left keep their own IDs so
+// they still count in everyone's all-play.
+fn prior_matchups(
+    history: &[PastMatchup],
+    season: &str,
+    teams: &[&FantasyTeam],
+    weeks: usize,
+) -> Vec<FantasyMatchup> {
+    let regular = || history.iter().filter(|m| !m.playoff);
+    let last_season = match regular()
+        .map(|m| m.season.as_str())
+        .filter(|s| *s < season)
+        .max()
+    {
+        Some(s) => s,
+        None => return vec![],
+    };
+    let mut week_nums: Vec<u32> = regular()
+        .filter(|m| m.season == last_season)
+        .map(|m| m.week_num)
+        .collect();
+    week_nums.sort_unstable();
+    week_nums.dedup();
+    let week_nums = &week_nums[week_nums.len().saturating_sub(weeks)..];
+
+    let team = |past: &PastTeam| {
+        teams
+            .iter()
+            .find(|t| t.id == past.owner_id || t.owner_name == past.owner_name)
+            .map_or_else(
+                || FantasyTeam {
+                    id: past.owner_id.clone(),
+                    team_name: past.team_name.clone(),
+                    owner_name: past.owner_name.clone(),
+                    avatar_url: None,
+                },
+                |t| (*t).clone(),
+            )
+    };
+    regular()
+        .filter(|m| m.season == last_season && week_nums.contains(&m.week_num))
+        .map(|m| FantasyMatchup {
+            team1: team(&m.team1),
+            team2: team(&m.team2),
+            score1: Some(m.score1),
+            score2: Some(m.score2),
+            week_num: m.week_num,
+        })
+        .collect()
+}
+
+// Adds each team's points and all-play record from a set of games to what they're rated on.
+fn rate<'a>(
+    matchups: &'a [FantasyMatchup],
+    points: &mut HashMap<&'a str, (f64, u32)>,
+    all_play: &mut HashMap<&'a str, Record>,
+) {
+    for m in matchups.iter() {
+        if let (Some(s1), Some(s2)) = (m.score1, m.score2) {
+            for (team, score) in [(&m.team1, s1), (&m.team2, s2)] {
+                let p = points.entry(&team.id).or_default();
+                p.0 += score;
+                p.1 += 1;
+            }
+        }
+    }
+    for (team, week, _) in weekly_all_play(matchups) {
+        all_play.entry(&team.id).or_default().merge(&week);
+    }
+}
+
+// Strength of schedule for every team in `season`, so far and from here on, rating opponents
+// by the games that have been played and, early in the season, the league's history.
+pub fn strength_of_schedule(
+    schedule: &Schedule,
+    history: &[PastMatchup],
+    season: &str,
+) -> Vec<TeamSchedule> {
+    let played = schedule.played_matchups();
+    let remaining = schedule.remaining_matchups();
+
+    let mut teams: Vec<&FantasyTeam> = vec![];
+    for m in played.iter().chain(remaining.iter()) {
+        for team in [&m.team1, &m.team2] {
+            if !teams.iter().any(|t| t.id == team.id) {
+                teams.push(team);
+            }
+        }
+    }
+    let weeks_played = schedule.weeks.iter().filter(|w| w.played).count();
+    let prior = prior_matchups(
+        history,
+        season,
+        &teams,
+        PRIOR_WEEKS.saturating_sub(weeks_played),
+    );
+
+    let mut points: HashMap<&str, (f64, u32)> = HashMap::new();
+    let mut all_play: HashMap<&str, Record> = HashMap::new();
+    rate(&played, &mut points, &mut all_play);
+    rate(&prior, &mut points, &mut all_play);
+
+    let strength = |team: &FantasyTeam, matchups: &[FantasyMatchup]| {
+        let opponents: Vec<&FantasyTeam> = matchups
+            .iter()
+            .filter_map(|m| {
+                if m.team1.id == team.id {
+                    Some(&m.team2)
+                } else if m.team2.id == team.id {
+                    Some(&m.team1)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        if opponents.is_empty() {
+            return None;
+        }
+        let n = opponents.len() as f64;
+        Some(Strength {
+            games: opponents.len() as u32,
+            opponent_points: opponents
+                .iter()
+                .map(|o| {
+                    points
+                        .get(o.id.as_str())
+                        .map_or(0.0, |(p, g)| p / *g as f64)
+                })
+                .sum::<f64>()
+                / n,
+            opponent_all_play: opponents
+                .iter()
+                .map(|o| all_play.get(o.id.as_str()).map_or(0.5, |r| r.win_pct()))
+                .sum::<f64>()
+                / n,
+        })
+    };
+
+    teams
+        .into_iter()
+        .map(|team| TeamSchedule {
+            team: team.clone(),
+            past: strength(team, &played),
+            remaining: strength(team, &remaining),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::ScheduleWeek;
+
+    fn week(week_num: u32, played: bool, matchups: Vec<FantasyMatchup>) -> ScheduleWeek {
+        ScheduleWeek {
+            week_num,
+            matchups,
+            played,
+        }
+    }
+
+    #[test]
+    fn all_play_and_luck() {
+        // B has the second best score both weeks but keeps running into A
+        let matchups = vec![
+            FantasyMatchup::test(1, "A", "B", Some((130.0, 120.0))),
+            FantasyMatchup::test(1, "C", "D", Some((90.0, 80.0))),
+            FantasyMatchup::test(2, "A", "B", Some((140.0, 110.0))),
+            FantasyMatchup::test(2, "C", "D", Some((100.0, 70.0))),
+        ];
+        let luck = luck(&matchups);
+        let by_id = |id: &str| luck.iter().find(|t| t.team.id == id).unwrap();
+
+        let a = by_id("A");
+        assert_eq!(
+            a.record,
+            Record {
+                wins: 2,
+                losses: 0,
+                ties: 0
+            }
+        );
+        assert_eq!(a.all_play.to_string(), "6-0");
+        assert_eq!(a.expected_wins, 2.0);
+
+        let b = by_id("B");
+        assert_eq!(b.all_play.to_string(), "4-2");
+        assert!((b.luck + 4.0 / 3.0).abs() < 1e-9);
+        assert_eq!(b.points_against_rank, 1);
+
+        let c = by_id("C");
+        assert!((c.luck - 4.0 / 3.0).abs() < 1e-9);
+        assert_eq!(luck[0].team.id, "C");
+    }
+
+    #[test]
+    fn strength_of_schedule_past_and_remaining() {
+        let schedule = Schedule {
+            weeks: vec![
+                week(
+                    1,
+                    true,
+                    vec![
+                        FantasyMatchup::test(1, "A", "B", Some((130.0, 120.0))),
+                        FantasyMatchup::test(1, "C", "D", Some((90.0, 80.0))),
+                    ],
+                ),
+                week(
+                    2,
+                    true,
+                    vec![
+                        FantasyMatchup::test(2, "A", "B", Some((140.0, 110.0))),
+                        FantasyMatchup::test(2, "C", "D", Some((100.0, 70.0))),
+                    ],
+                ),
+                // scores for weeks that haven't been played don't count
+                week(
+                    3,
+                    false,
+                    vec![
+                        FantasyMatchup::test(3, "A", "D", Some((0.0, 0.0))),
+                        FantasyMatchup::test(3, "B", "C", Some((0.0, 0.0))),
+                    ],
+                ),
+            ],
+        };
+        let sos = strength_of_schedule(&schedule, &[], "2021");
+        let by_id = |id: &str| sos.iter().find(|t| t.team.id == id).unwrap();
+
+        // A has only seen B, who's 4-2 in all-play and averages 115
+        let a = by_id("A");
+        let past = a.past.as_ref().unwrap();
+        assert_eq!(past.games, 2);
+        assert_eq!(past.opponent_points, 115.0);
+        assert!((past.opponent_all_play - 4.0 / 6.0).abs() < 1e-9);
+        // and gets winless D next
+        let remaining = a.remaining.as_ref().unwrap();
+        assert_eq!(remaining.games, 1);
+        assert_eq!(remaining.opponent_all_play, 0.0);
+
+        // B's faced the best team and gets an easier one next
+        let b = by_id("B");
+        assert_eq!(b.past.as_ref().unwrap().opponent_all_play, 1.0);
+        assert!((b.remaining.as_ref().unwrap().opponent_all_play - 2.0 / 6.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn strength_of_schedule_leans_on_last_season_early() {
+        let team = |id: &str| PastTeam::test(&format!("Owner {}", id), &format!("Team {}", id));
+        let past = |season: &str, week: u32, a: &str, b: &str, s1: f64, s2: f64| {
+            PastMatchup::test(season, week, &team(a), &team(b), s1, s2)
+        };
+        // C ran the table at the end of last season; only the last few weeks count
+        let mut history: Vec<PastMatchup> = (1..=13)
+            .flat_map(|w| {
+                vec![
+                    past("2020", w, "A", "C", 150.0, 50.0),
+                    past("2020", w, "B", "D", 90.0, 80.0),
+                ]
+            })
+            .collect();
+        history.extend((1..=14).flat_map(|w| {
+            vec![
+                past("2021", w, "C", "A", 150.0, 50.0),
+                past("2021", w, "B", "D", 90.0, 80.0),
+            ]
+        }));
+        history.push(PastMatchup {
+            playoff: true,
+            ..past("2021", 15, "D", "C", 200.0, 10.0)
+        });
+
+        let schedule = Schedule {
+            weeks: vec![week(
+                1,
+                false,
+                vec![
+                    FantasyMatchup::test(1, "A", "C", Some((0.0, 0.0))),
+                    FantasyMatchup::test(1, "B", "D", Some((0.0, 0.0))),
+                ],
+            )],
+        };
+        let sos = strength_of_schedule(&schedule, &history, "2022");
+        let remaining = |id: &str| {
+            let t = sos.iter().find(|t| t.team.id == id).unwrap();
+            assert!(t.past.is_none());
+            t.remaining.as_ref().unwrap().opponent_all_play
+        };
+        assert_eq!(remaining("A"), 1.0);
+        assert_eq!(remaining("C"), 0.0);
+        assert!((remaining("D") - 2.0 / 3.0).abs() < 1e-9);
+        let a = sos.iter().find(|t| t.team.id == "A").unwrap();
+        assert_eq!(a.remaining.as_ref().unwrap().opponent_points, 150.0);
+
+        // once a few weeks are in, it's this season alone
+        let history = vec![past("2021", 14, "A", "C", 150.0, 50.0)];
+        let schedule = Schedule {
+            weeks: (1..=4)
+                .map(|w| {
+                    week(
+                        w,
+                        true,
+                        vec![FantasyMatchup::test(w, "A", "C", Some((100.0, 120.0)))],
+                    )
+                })
+                .chain(vec![week(
+                    5,
+                    false,
+                    vec![FantasyMatchup::test(5, "A", "C", Some((0.0, 0.0)))],
+                )])
+                .collect(),
+        };
+        let sos = strength_of_schedule(&schedule, &history, "2022");
+        let a = sos.iter().find(|t| t.team.id == "A").unwrap();
+        assert_eq!(a.remaining.as_ref().unwrap().opponent_points, 120.0);
+    }
+}
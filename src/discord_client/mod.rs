@@ -1,22 +1,136 @@
-use super::fantasy_client::{FflClient, LeagueType};
+use super::analytics;
+use super::draft;
+use super::fantasy_client::sleeper::{
+    get_nfl_state, get_trending, player_image_url, SleeperPlayers,
+};
+use super::fantasy_client::{
+    get_power_rankings, Draft, DraftStatus, FaabBalance, FantasyTeam, FflClient, KeeperRules,
+    PlayerProjection,
+};
+use super::history;
+use super::keepers::{self, TeamKeepers};
+use super::lineup::{self, LineupIssue};
+use super::nfl;
+use super::players::{self, LeaguePlayer};
+use super::playoffs::{self, PlayoffOdds, Season};
+use super::records;
+use super::render::{self, Reply};
+use super::startsit::PlayerOutlook;
+use super::storage::{Storage, DATA_DIR};
+use super::trending;
+use super::waivers;
+use pagination::Paginator;
 use phf::phf_map;
 use regex::Regex;
+use scheduled::Scheduler;
 use serde::Deserialize;
 use serenity::{
     async_trait,
+    builder::{CreateEmbed, CreateInteractionResponseData, EditInteractionResponse},
     model::{
         channel::Message,
         gateway::Ready,
+        id::{ChannelId, GuildId},
         interactions::{
-            application_command::{ApplicationCommand, ApplicationCommandOptionType},
+            application_command::{
+                ApplicationCommand, ApplicationCommandInteraction, ApplicationCommandOptionType,
+            },
+            message_component::MessageComponentInteraction,
             Interaction, InteractionResponseType,
         },
     },
     prelude::*,
 };
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+mod pagination;
+mod scheduled;
 use std::option::Option;
 
+// league-specific commands, registered per guild with that guild's leagues as choices.
+// Each one can also take some string options of its own.
+const LEAGUE_COMMANDS: [(&str, &str, &[CommandOption]); 18] = [
+    ("matchups", "Fetch this week's matchups", &[]),
+    ("standings", "Fetch the current standings", &[]),
+    ("power", "Fetch power rankings", &[]),
+    ("luck", "All-play records and who's been lucky", &[]),
+    (
+        "sos",
+        "Strength of schedule so far and for the rest of the season",
+        &[],
+    ),
+    (
+        "playoffs",
+        "Each team's chances of making the playoffs and winning it all",
+        &[],
+    ),
+    (
+        "h2h",
+        "All-time head-to-head between two teams or owners",
+        &[
+            ("team1", "a team or owner name", true),
+            ("team2", "another team or owner name", true),
+        ],
+    ),
+    ("records", "The league's all-time record book", &[]),
+    ("bracket", "The playoff bracket", &[]),
+    ("settings", "A summary of the league's rules", &[]),
+    (
+        "draft",
+        "Everyone's draft picks, graded once the season's over",
+        &[],
+    ),
+    (
+        "board",
+        "The best players still available to draft",
+        &[("position", "only show this position, e.g. RB", false)],
+    ),
+    (
+        "keepers",
+        "Who each team can keep next season, what it costs and whether it's worth it",
+        &[],
+    ),
+    (
+        "roster",
+        "Every player on each team's roster",
+        &[("team", "only show teams whose name contains this", false)],
+    ),
+    (
+        "lineup-check",
+        "Check starting lineups for byes, injuries and empty slots",
+        &[("team", "only check teams whose name contains this", false)],
+    ),
+    (
+        "startsit",
+        "Compare players to decide who to start",
+        &[(
+            "players",
+            "two or more player names, separated by commas",
+            true,
+        )],
+    ),
+    (
+        "waivers",
+        "Find the best available free agents",
+        &[("position", "only show this position, e.g. RB", false)],
+    ),
+    (
+        "trending",
+        "Players being added or dropped the most on Sleeper",
+        &[
+            ("direction", "add or drop, adds by default", false),
+            ("hours", "how far back to look, 24 by default", false),
+        ],
+    ),
+];
+
+// (name, description, required)
+type CommandOption = (&'static str, &'static str, bool);
+
 static REACC_MAP: phf::Map<&str, char> = phf_map! {
     "football" => '🏈',
     "butt" => '🍑',
@@ -34,18 +148,6 @@ struct CovidPlayer {
     search_rank: Option<u64>,
 }
 
-#[derive(Deserialize, Debug)]
-struct PowerResponse {
-    power: Vec<TeamPower>,
-    updated: String,
-}
-
-#[derive(Deserialize, Debug)]
-struct TeamPower {
-    power: String,
-    team: String,
-}
-
 impl DiscordClient {
     pub async fn new(
         token: String,
@@ -54,12 +156,17 @@ impl DiscordClient {
         ffl_clients: Vec<FflClient>,
         covid_json_url: String,
         power_ranking_url_format: String,
+        sleeper_players: Arc<SleeperPlayers>,
     ) -> DiscordClient {
         let handler = Handler {
             ignore_reaccs,
-            ffl_clients,
+            ffl_clients: Arc::new(ffl_clients),
             covid_json_url,
             power_ranking_url_format,
+            sleeper_players,
+            paginator: Arc::new(Paginator::default()),
+            storage: Arc::new(Storage::new(DATA_DIR)),
+            scheduler_started: AtomicBool::new(false),
         };
         let client = Client::builder(token)
             .event_handler(handler)
@@ -76,9 +183,13 @@ impl DiscordClient {
 
 struct Handler {
     ignore_reaccs: Vec<(String, String)>,
-    ffl_clients: Vec<FflClient>,
+    ffl_clients: Arc<Vec<FflClient>>,
     covid_json_url: String,
     power_ranking_url_format: String,
+    sleeper_players: Arc<SleeperPlayers>,
+    paginator: Arc<Paginator>,
+    storage: Arc<Storage>,
+    scheduler_started: AtomicBool,
 }
 
 #[async_trait]
@@ -86,66 +197,122 @@ impl EventHandler for Handler {
     async fn interaction_create(&self, ctx: Context, interaction: Interaction) {
         println!("got interaction: {:?}", interaction);
         if let Interaction::ApplicationCommand(slash_command) = interaction {
+            // plenty of commands take longer than the 3 seconds Discord gives us to answer, so
+            // answer right away and fill the reply in once it's ready
+            if let Err(e) = slash_command
+                .create_interaction_response(&ctx.http, |response| {
+                    response.kind(InteractionResponseType::DeferredChannelMessageWithSource)
+                })
+                .await
+            {
+                println!("failed to defer slash command: {}", e);
+                return;
+            }
             let command = slash_command.data.name.as_str();
-            let reply: Option<String>;
+            let reply: Option<Vec<Reply>>;
             if command == "whosgotcovid" {
                 reply = self.handle_whosgotcovid().await;
+            } else if command == "nflscores" {
+                reply = self.handle_nflscores().await;
+            } else if command == "player" {
+                let guild_id = slash_command
+                    .guild_id
+                    .map(|g| g.as_u64().to_string())
+                    .unwrap_or_default();
+                reply = self
+                    .handle_player(&guild_id, get_option(&slash_command, "name"))
+                    .await;
             } else {
-                let mut league_name: Option<String> = None;
-                for option in slash_command.data.options.iter() {
-                    if option.name == "league" {
-                        league_name = option.value.clone().map(|v| v.as_str().unwrap().to_owned());
-                        break;
-                    }
-                }
+                let league_name = get_option(&slash_command, "league");
                 println!(
                     "received slash command: {:?} for league {:?}",
                     slash_command, league_name,
                 );
                 println!("channel ID: {:?}", slash_command.channel_id);
+                let guild_id = slash_command
+                    .guild_id
+                    .map(|g| g.as_u64().to_string())
+                    .unwrap_or_default();
                 let ffl_client = match league_name {
-                    Some(n) => self.get_client_by_name(n),
+                    Some(n) => self.get_client_by_name(&guild_id, n),
                     None => {
-                        // what the shit is this I just want to get the category ID
-                        let category = slash_command
-                            .channel_id
-                            .to_channel(&ctx.http)
-                            .await
-                            .unwrap()
-                            .guild()
-                            .unwrap()
-                            .category_id
-                            .unwrap()
-                            .to_channel(&ctx.http)
+                        self.get_client_by_channel(&ctx, slash_command.channel_id)
                             .await
-                            .unwrap()
-                            .category();
-                        println!("attempting to get league from category {:?}", category);
-                        let category_id = category.unwrap().id.as_u64().to_string();
-                        self.get_client_by_category_id(category_id)
                     }
-                }
-                .unwrap();
+                };
+                let ffl_client = match ffl_client {
+                    Some(c) => c,
+                    None => {
+                        self.send_reply(
+                            &ctx,
+                            &slash_command,
+                            vec![render::text_block(
+                                "There's no league for this channel, pick one with the league option.",
+                            )],
+                        )
+                        .await;
+                        return;
+                    }
+                };
 
                 reply = match command {
-                    "matchups" => self.handle_matchups().await,
-                    "standings" => self.handle_standings().await,
-                    "power" => self.handle_power(&ffl_client).await,
+                    "matchups" => self.handle_matchups(ffl_client).await,
+                    "standings" => self.handle_standings(ffl_client).await,
+                    "power" => self.handle_power(ffl_client).await,
+                    "luck" => self.handle_luck(ffl_client).await,
+                    "sos" => self.handle_sos(ffl_client).await,
+                    "playoffs" => self.handle_playoffs(ffl_client).await,
+                    "records" => self.handle_records(ffl_client).await,
+                    "bracket" => self.handle_bracket(ffl_client).await,
+                    "settings" => self.handle_settings(ffl_client).await,
+                    "draft" => self.handle_draft(ffl_client).await,
+                    "keepers" => self.handle_keepers(ffl_client).await,
+                    "board" => {
+                        self.handle_board(ffl_client, get_option(&slash_command, "position"))
+                            .await
+                    }
+                    "h2h" => {
+                        self.handle_h2h(
+                            ffl_client,
+                            get_option(&slash_command, "team1"),
+                            get_option(&slash_command, "team2"),
+                        )
+                        .await
+                    }
+                    "startsit" => {
+                        self.handle_startsit(ffl_client, get_option(&slash_command, "players"))
+                            .await
+                    }
+                    "waivers" => {
+                        self.handle_waivers(ffl_client, get_option(&slash_command, "position"))
+                            .await
+                    }
+                    "trending" => {
+                        self.handle_trending(
+                            ffl_client,
+                            get_option(&slash_command, "direction"),
+                            get_option(&slash_command, "hours"),
+                        )
+                        .await
+                    }
+                    "roster" => {
+                        self.handle_roster(ffl_client, get_option(&slash_command, "team"))
+                            .await
+                    }
+                    "lineup-check" => {
+                        self.handle_lineup_check(ffl_client, get_option(&slash_command, "team"))
+                            .await
+                    }
                     _ => None,
                 };
             }
 
-            let answer = reply.unwrap();
-            println!("replying with message {}", answer);
-            if let Err(e) = slash_command
-                .create_interaction_response(&ctx.http, |response| {
-                    response
-                        .kind(InteractionResponseType::ChannelMessageWithSource)
-                        .interaction_response_data(|message| message.content(answer))
-                })
-                .await
-            {
-                println!("failed to respond to slash command: {}", e);
+            let pages =
+                reply.unwrap_or_else(|| vec![render::text_block("I don't know that command.")]);
+            self.send_reply(&ctx, &slash_command, pages).await;
+        } else if let Interaction::MessageComponent(component) = interaction {
+            if Paginator::is_page_button(&component.data.custom_id) {
+                self.handle_page_turn(&ctx, &component).await;
             }
         }
     }
@@ -182,41 +349,113 @@ impl EventHandler for Handler {
     async fn ready(&self, ctx: Context, ready: Ready) {
         println!("{} is connected!", ready.user.name);
         let commands = ApplicationCommand::set_global_application_commands(&ctx.http, |commands| {
-            for command_config in vec![
-                ("matchups", "Fetch this week's matchups"),
-                ("standings", "Fetch the current standings"),
-                ("power", "Fetch power rankings"),
-            ] {
-                commands.create_application_command(|command| {
-                    command
-                        .name(command_config.0)
-                        .description(command_config.1)
-                        .create_option(|option| {
-                            for client in self.ffl_clients.iter() {
-                                option.add_string_choice(
-                                    client.config.league_name.clone(),
-                                    client.config.short_name.clone(),
-                                );
-                            }
-                            option
-                                .kind(ApplicationCommandOptionType::String)
-                                .name("league")
-                                .description("which league?")
-                        })
-                });
-            }
             commands.create_application_command(|command| {
                 command
                     .name("whosgotcovid")
                     .description("the COVID naughty list")
             });
-            println!("trying to create commands: {:?}", commands);
+            commands.create_application_command(|command| {
+                command
+                    .name("nflscores")
+                    .description("This week's NFL scores")
+            });
+            commands.create_application_command(|command| {
+                command
+                    .name("player")
+                    .description("Look up a player across this server's leagues")
+                    .create_option(|option| {
+                        option
+                            .kind(ApplicationCommandOptionType::String)
+                            .name("name")
+                            .description("the player's name")
+                            .required(true)
+                    })
+            });
+            println!("trying to create global commands: {:?}", commands);
             commands
         })
         .await
         .unwrap();
 
-        println!("Created slash commands: {:#?}", commands);
+        println!("Created global slash commands: {:#?}", commands);
+
+        let mut clients_by_guild: HashMap<GuildId, Vec<&FflClient>> = HashMap::new();
+        for client in self.ffl_clients.iter() {
+            let config = &client.config;
+            let guild_id = match config.guild_id.as_deref().map(str::parse::<u64>) {
+                Some(Ok(id)) => GuildId(id),
+                Some(Err(e)) => {
+                    println!("invalid guild ID for league {}: {}", config.league_name, e);
+                    continue;
+                }
+                None => {
+                    println!(
+                        "league {} has no guild ID, so it has no commands",
+                        config.league_name
+                    );
+                    continue;
+                }
+            };
+            clients_by_guild.entry(guild_id).or_default().push(client);
+        }
+
+        for (guild_id, clients) in clients_by_guild {
+            let commands = guild_id
+                .set_application_commands(&ctx.http, |commands| {
+                    for command_config in LEAGUE_COMMANDS {
+                        commands.create_application_command(|command| {
+                            command
+                                .name(command_config.0)
+                                .description(command_config.1)
+                                .create_option(|option| {
+                                    for client in clients.iter() {
+                                        option.add_string_choice(
+                                            client.config.league_name.clone(),
+                                            client.config.short_name.clone(),
+                                        );
+                                    }
+                                    option
+                                        .kind(ApplicationCommandOptionType::String)
+                                        .name("league")
+                                        .description("which league?")
+                                });
+                            for (name, description, required) in command_config.2.iter() {
+                                command.create_option(|option| {
+                                    option
+                                        .kind(ApplicationCommandOptionType::String)
+                                        .name(name)
+                                        .description(description)
+                                        .required(*required)
+                                });
+                            }
+                            command
+                        });
+                    }
+                    println!(
+                        "trying to create commands for guild {}: {:?}",
+                        guild_id, commands
+                    );
+                    commands
+                })
+                .await;
+
+            match commands {
+                Ok(c) => println!("Created slash commands for guild {}: {:#?}", guild_id, c),
+                Err(e) => println!("failed to create commands for guild {}: {}", guild_id, e),
+            }
+        }
+
+        // ready fires again on reconnects, but we only want one copy of the background jobs
+        if !self.scheduler_started.swap(true, Ordering::SeqCst) {
+            let scheduler = Scheduler {
+                http: ctx.http.clone(),
+                ffl_clients: self.ffl_clients.clone(),
+                storage: self.storage.clone(),
+                power_ranking_url_format: self.power_ranking_url_format.clone(),
+                sleeper_players: self.sleeper_players.clone(),
+            };
+            tokio::spawn(scheduler.run());
+        }
 
         //let channel = ctx.http.get_channel(BOT_CHANNEL).await.unwrap();
         //channel.id().send_message(ctx, |m| {
@@ -226,47 +465,169 @@ impl EventHandler for Handler {
 }
 
 impl Handler {
+    // Remembers the pages behind a message and drops its buttons once they expire.
+    fn track_pages(&self, ctx: &Context, message: Message, pages: Vec<Reply>) {
+        self.paginator.insert(message.id, pages);
+        let paginator = self.paginator.clone();
+        let http = ctx.http.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(pagination::PAGE_TIMEOUT).await;
+            paginator.remove(&message.id);
+            if let Err(e) = message
+                .channel_id
+                .edit_message(&http, message.id, |m| m.components(|c| c))
+                .await
+            {
+                println!("failed to remove page buttons: {}", e);
+            }
+        });
+    }
+
+    async fn handle_page_turn(&self, ctx: &Context, component: &MessageComponentInteraction) {
+        let page = self
+            .paginator
+            .turn(&component.message.id, &component.data.custom_id);
+        let result = component
+            .create_interaction_response(&ctx.http, |response| {
+                response
+                    .kind(InteractionResponseType::UpdateMessage)
+                    .interaction_response_data(|message| match &page {
+                        Some((reply, current, total)) => {
+                            set_reply(message, reply);
+                            message.components(|c| pagination::page_buttons(c, *current, *total))
+                        }
+                        // we've forgotten about this message, so just drop the buttons
+                        None => message.components(|c| c),
+                    })
+            })
+            .await;
+        if let Err(e) = result {
+            println!("failed to turn page: {}", e);
+        }
+    }
+
+    // Fills in a deferred response with the first page of a reply, with buttons for the rest.
+    async fn send_reply(
+        &self,
+        ctx: &Context,
+        slash_command: &ApplicationCommandInteraction,
+        mut pages: Vec<Reply>,
+    ) {
+        println!("replying with message {:?}", pages);
+        if pages.is_empty() {
+            pages.push(render::text_block("Nothing to show."));
+        }
+        let sent = slash_command
+            .edit_original_interaction_response(&ctx.http, |response| {
+                edit_reply(response, &pages[0]);
+                if pages.len() > 1 {
+                    response.components(|c| pagination::page_buttons(c, 0, pages.len()));
+                }
+                response
+            })
+            .await;
+        match sent {
+            Ok(sent) if pages.len() > 1 => self.track_pages(ctx, sent, pages),
+            Ok(_) => {}
+            Err(e) => println!("failed to respond to slash command: {}", e),
+        }
+    }
+
+    // The league whose category the channel is in, if any.
+    async fn get_client_by_channel(
+        &self,
+        ctx: &Context,
+        channel_id: ChannelId,
+    ) -> Option<&FflClient> {
+        let channel = match channel_id.to_channel(&ctx.http).await {
+            Ok(c) => c,
+            Err(e) => {
+                println!("failed to fetch channel {}: {}", channel_id, e);
+                return None;
+            }
+        };
+        let category_id = channel.guild()?.category_id?;
+        println!("attempting to get league from category {}", category_id);
+        self.get_client_by_category_id(category_id.as_u64().to_string())
+    }
+
     fn get_client_by_category_id(&self, id: String) -> Option<&FflClient> {
         let mut ret: Option<&FflClient> = None;
         for client in self.ffl_clients.iter() {
             if client.config.discord_category_id == id {
-                ret = Some(&client);
+                ret = Some(client);
                 break;
             }
         }
         ret
     }
 
-    fn get_client_by_name(&self, name: String) -> Option<&FflClient> {
+    fn get_client_by_name(&self, guild_id: &str, name: String) -> Option<&FflClient> {
         let mut ret: Option<&FflClient> = None;
         for client in self.ffl_clients.iter() {
-            if client.config.short_name == name {
-                ret = Some(&client);
+            if client.config.guild_id.as_deref() == Some(guild_id)
+                && client.config.short_name == name
+            {
+                ret = Some(client);
                 break;
             }
         }
         ret
     }
 
-    async fn handle_matchups(&self) -> Option<String> {
-        Some(
-            "```
-this is a matchups response
-```"
-            .to_string(),
-        )
+    async fn handle_matchups(&self, ffl_client: &FflClient) -> Option<Vec<Reply>> {
+        let matchups = match ffl_client.client().get_matchups(None).await {
+            Ok(m) => m,
+            Err(e) => {
+                println!("failed to fetch matchups: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch matchups, try again later.",
+                )]);
+            }
+        };
+        let week_num = matchups.first().map_or(0, |m| m.week_num);
+        // during the playoffs, show the bracket's games instead of everyone's
+        match ffl_client.client().get_brackets().await {
+            Ok(brackets) if brackets.iter().any(|b| !b.current_games().is_empty()) => {
+                return Some(render::playoff_matchups(&brackets));
+            }
+            Ok(_) => {}
+            Err(e) => println!("failed to fetch playoff brackets: {}", e),
+        }
+        Some(render::matchups(week_num, &matchups))
     }
 
-    async fn handle_standings(&self) -> Option<String> {
-        Some(
-            "```
-this is a standings response
-```"
-            .to_string(),
-        )
+    async fn handle_bracket(&self, ffl_client: &FflClient) -> Option<Vec<Reply>> {
+        let brackets = match ffl_client.client().get_brackets().await {
+            Ok(b) => b,
+            Err(e) => {
+                println!("failed to fetch playoff brackets: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch the playoff bracket, try again later.",
+                )]);
+            }
+        };
+        Some(render::bracket(&ffl_client.config.league_name, &brackets))
+    }
+
+    async fn handle_standings(&self, ffl_client: &FflClient) -> Option<Vec<Reply>> {
+        let standings = match ffl_client.client().get_standings().await {
+            Ok(s) => s,
+            Err(e) => {
+                println!("failed to fetch standings: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch standings, try again later.",
+                )]);
+            }
+        };
+        Some(render::standings(
+            &ffl_client.config.league_name,
+            render::league_color(&ffl_client.config.league_type),
+            &standings,
+        ))
     }
 
-    async fn handle_whosgotcovid(&self) -> Option<String> {
+    async fn handle_whosgotcovid(&self) -> Option<Vec<Reply>> {
         let covid_resp = reqwest::get(&self.covid_json_url)
             .await
             .unwrap()
@@ -286,54 +647,755 @@ this is a standings response
                 ))
             }
         }
-        if covid_players.len() == 0 {
-            Some(
-                "```
-Nobody, apparently.
-```"
-                .to_string(),
-            )
+        if covid_players.is_empty() {
+            Some(vec![render::text_block("Nobody, apparently.")])
         } else {
-            Some(format!("```{}```", covid_players.join("\n")))
+            Some(render::paginate_text(&covid_players.join("\n")))
         }
     }
 
-    async fn handle_power(&self, ffl_client: &FflClient) -> Option<String> {
-        let id = &ffl_client.config.league_id;
-        let league_type = match ffl_client.config.league_type {
-            LeagueType::SLEEPER => "sleeper",
-            LeagueType::ESPN => "espn",
-            _ => panic!("wtf league type is that"),
+    async fn handle_nflscores(&self) -> Option<Vec<Reply>> {
+        let state = match get_nfl_state().await {
+            Ok(s) => s,
+            Err(e) => {
+                println!("failed to get NFL state: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch NFL scores, try again later.",
+                )]);
+            }
         };
-        println!("getting power for league {} of type {}", id, league_type);
+        match nfl::get_games(&state.season, state.week).await {
+            Ok(games) => Some(render::nfl_scores(state.week, &games)),
+            Err(e) => {
+                println!("failed to fetch NFL games: {}", e);
+                Some(vec![render::text_block(
+                    "Couldn't fetch NFL scores, try again later.",
+                )])
+            }
+        }
+    }
 
-        if league_type == "sleeper" {
-            Some("power rankings for sleeper not implemented yet sorryyyyyyyy".to_string())
-        } else {
-            let url = self
-                .power_ranking_url_format
-                .clone()
-                .replace("<LEAGUE_ID>", id)
-                .replace("<LEAGUE_TYPE>", league_type);
-            println!("fetching power from URL {}", url);
-            let power_resp = reqwest::get(url)
-                .await
-                .unwrap()
-                .json::<PowerResponse>()
-                .await
-                .unwrap();
-            let mut lines = vec![];
-            for team in power_resp.power.iter() {
-                lines.push(format!("{} ({})", team.team, team.power))
-            }
-            lines.push(format!("\nupdated {}", power_resp.updated));
-
-            Some(format!(
-                "```
-{}
-```",
-                lines.join("\n")
-            ))
+    async fn handle_roster(
+        &self,
+        ffl_client: &FflClient,
+        team_filter: Option<String>,
+    ) -> Option<Vec<Reply>> {
+        let mut rosters = match ffl_client.client().get_rosters().await {
+            Ok(r) => r,
+            Err(e) => {
+                println!("failed to get rosters: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch rosters, try again later.",
+                )]);
+            }
+        };
+        if let Some(filter) = team_filter {
+            let filter = filter.to_lowercase();
+            rosters.retain(|r| r.team.team_name.to_lowercase().contains(&filter));
+        }
+        if rosters.is_empty() {
+            return Some(vec![render::text_block("No team by that name.")]);
+        }
+        Some(render::rosters(&rosters))
+    }
+
+    async fn handle_lineup_check(
+        &self,
+        ffl_client: &FflClient,
+        team_filter: Option<String>,
+    ) -> Option<Vec<Reply>> {
+        let (week_num, mut checks) = match lineup_checks(ffl_client, &self.storage).await {
+            Ok(c) => c,
+            Err(e) => {
+                println!("failed to check lineups: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't check lineups, try again later.",
+                )]);
+            }
+        };
+        if let Some(filter) = team_filter {
+            let filter = filter.to_lowercase();
+            checks.retain(|(team, _)| team.team_name.to_lowercase().contains(&filter));
+        }
+        Some(render::lineup_check(week_num, &checks))
+    }
+
+    async fn handle_startsit(
+        &self,
+        ffl_client: &FflClient,
+        players: Option<String>,
+    ) -> Option<Vec<Reply>> {
+        let names: Vec<String> = players
+            .unwrap_or_default()
+            .split(',')
+            .map(|n| n.trim().to_string())
+            .filter(|n| !n.is_empty())
+            .collect();
+        if names.len() < 2 {
+            return Some(vec![render::text_block(
+                "Give me at least two players, separated by commas.",
+            )]);
+        }
+        let (week_num, found) = match player_outlooks(ffl_client, &names).await {
+            Ok(o) => o,
+            Err(e) => {
+                println!("failed to look up players: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't look up those players, try again later.",
+                )]);
+            }
+        };
+        let mut outlooks = vec![];
+        let mut missing = vec![];
+        for (name, outlook) in names.into_iter().zip(found) {
+            match outlook {
+                Some(o) => outlooks.push(o),
+                None => missing.push(name),
+            }
         }
+        Some(render::startsit(week_num, &outlooks, &missing))
+    }
+
+    async fn handle_waivers(
+        &self,
+        ffl_client: &FflClient,
+        position: Option<String>,
+    ) -> Option<Vec<Reply>> {
+        let (weeks_left, free_agents, faab) = match free_agents(ffl_client).await {
+            Ok(w) => w,
+            Err(e) => {
+                println!("failed to fetch free agents: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch free agents, try again later.",
+                )]);
+            }
+        };
+        let position = position.as_deref().map(waivers::normalize_position);
+        let ranked = waivers::rank(free_agents, position.as_deref(), weeks_left);
+        Some(render::waivers(
+            &ffl_client.config.league_name,
+            render::league_color(&ffl_client.config.league_type),
+            position.as_deref(),
+            &ranked,
+            &faab,
+        ))
+    }
+
+    async fn handle_trending(
+        &self,
+        ffl_client: &FflClient,
+        direction: Option<String>,
+        hours: Option<String>,
+    ) -> Option<Vec<Reply>> {
+        let kind = match direction.as_deref().map(|d| d.trim().to_lowercase()) {
+            Some(d) if d.starts_with("drop") => "drop",
+            _ => "add",
+        };
+        let hours = hours
+            .and_then(|h| h.trim().parse::<u32>().ok())
+            .unwrap_or(trending::DEFAULT_LOOKBACK_HOURS);
+        let trending = match get_trending(kind, hours, trending::TRENDING_LIMIT).await {
+            Ok(t) => t,
+            Err(e) => {
+                println!("failed to fetch trending players: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch trending players, try again later.",
+                )]);
+            }
+        };
+        let rosters = match ffl_client.client().get_rosters().await {
+            Ok(r) => r,
+            Err(e) => {
+                println!("failed to fetch rosters: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch rosters, try again later.",
+                )]);
+            }
+        };
+        let players = trending::build(
+            &trending,
+            &self.sleeper_players,
+            &ffl_client.config.league_type,
+            &rosters,
+        );
+        Some(render::trending(
+            &ffl_client.config.league_name,
+            kind,
+            hours,
+            &players,
+        ))
+    }
+
+    async fn handle_player(&self, guild_id: &str, name: Option<String>) -> Option<Vec<Reply>> {
+        let name = name.unwrap_or_default();
+        let player = match self.sleeper_players.search(&name) {
+            Some(p) => p,
+            None => {
+                return Some(vec![render::text_block(&format!(
+                    "Couldn't find anybody called {}.",
+                    name
+                ))])
+            }
+        };
+        let week_num = match get_nfl_state().await {
+            Ok(s) => Some(s.week),
+            Err(e) => {
+                println!("failed to get NFL state: {}", e);
+                None
+            }
+        };
+
+        let mut leagues = vec![];
+        for ffl_client in self.ffl_clients.iter() {
+            if ffl_client.config.guild_id.as_deref() != Some(guild_id) {
+                continue;
+            }
+            let client = ffl_client.client();
+            // other leagues might know the player by a different ID, so find them by name
+            let projection = match week_num {
+                Some(week) => match client
+                    .search_players(std::slice::from_ref(&player.name), week)
+                    .await
+                {
+                    Ok(mut found) => found.pop().flatten(),
+                    Err(e) => {
+                        println!("failed to search for player: {}", e);
+                        None
+                    }
+                },
+                None => None,
+            };
+            let rosters = match client.get_rosters().await {
+                Ok(r) => r,
+                Err(e) => {
+                    println!("failed to fetch rosters: {}", e);
+                    vec![]
+                }
+            };
+            leagues.push(LeaguePlayer {
+                league_name: ffl_client.config.league_name.clone(),
+                rostered_by: projection
+                    .as_ref()
+                    .and_then(|p| players::rostered_by(&p.player.id, &rosters))
+                    .cloned(),
+                projection,
+            });
+        }
+
+        Some(render::player_card(
+            &player,
+            self.sleeper_players.injury_start_date(&player.id),
+            &player_image_url(&player),
+            &leagues,
+        ))
+    }
+
+    async fn handle_luck(&self, ffl_client: &FflClient) -> Option<Vec<Reply>> {
+        let matchups = match ffl_client.client().get_schedule().await {
+            Ok(s) => s.played_matchups(),
+            Err(e) => {
+                println!("failed to fetch matchups for luck: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch matchups, try again later.",
+                )]);
+            }
+        };
+        Some(render::luck(
+            &ffl_client.config.league_name,
+            render::league_color(&ffl_client.config.league_type),
+            &analytics::luck(&matchups),
+        ))
+    }
+
+    async fn handle_sos(&self, ffl_client: &FflClient) -> Option<Vec<Reply>> {
+        let client = ffl_client.client();
+        let schedule = match client.get_schedule().await {
+            Ok(s) => s,
+            Err(e) => {
+                println!("failed to fetch schedule: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch the schedule, try again later.",
+                )]);
+            }
+        };
+        let season = match client.get_league_settings().await {
+            Ok(s) => s.season,
+            Err(e) => {
+                println!("failed to fetch league settings: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch the league settings, try again later.",
+                )]);
+            }
+        };
+        // last season only helps early on, so a league without a history just goes without
+        let league_id = &ffl_client.config.league_id;
+        let history = match history::stored(&self.storage, league_id) {
+            Some(h) => h,
+            None => match history::update(client, &self.storage, league_id).await {
+                Ok(h) => h,
+                Err(e) => {
+                    println!("failed to load league history: {}", e);
+                    vec![]
+                }
+            },
+        };
+        Some(render::sos(
+            &ffl_client.config.league_name,
+            render::league_color(&ffl_client.config.league_type),
+            &analytics::strength_of_schedule(&schedule, &history, &season),
+        ))
+    }
+
+    async fn handle_playoffs(&self, ffl_client: &FflClient) -> Option<Vec<Reply>> {
+        let (has_byes, odds) = match playoff_chances(ffl_client).await {
+            Ok(o) => o,
+            Err(e) => {
+                println!("failed to simulate playoffs: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't work out playoff odds, try again later.",
+                )]);
+            }
+        };
+        Some(render::playoffs(
+            &ffl_client.config.league_name,
+            render::league_color(&ffl_client.config.league_type),
+            has_byes,
+            playoffs::SIMULATIONS,
+            &odds,
+        ))
+    }
+
+    async fn handle_h2h(
+        &self,
+        ffl_client: &FflClient,
+        team1: Option<String>,
+        team2: Option<String>,
+    ) -> Option<Vec<Reply>> {
+        let (team1, team2) = match (team1, team2) {
+            (Some(t1), Some(t2)) => (t1, t2),
+            _ => return Some(vec![render::text_block("Give me two teams or owners.")]),
+        };
+        // the scheduler keeps the stored copy current, so it's only fetched here the first time
+        let league_id = &ffl_client.config.league_id;
+        let history = match history::stored(&self.storage, league_id) {
+            Some(h) => h,
+            None => match history::update(ffl_client.client(), &self.storage, league_id).await {
+                Ok(h) => h,
+                Err(e) => {
+                    println!("failed to load league history: {}", e);
+                    return Some(vec![render::text_block(
+                        "Couldn't fetch the league's history, try again later.",
+                    )]);
+                }
+            },
+        };
+        let (owner1, owner2) = match (
+            history::find_owner(&history, &team1),
+            history::find_owner(&history, &team2),
+        ) {
+            (Some(o1), Some(o2)) => (o1, o2),
+            (None, _) => return Some(vec![render::text_block(&no_team(&team1))]),
+            (_, None) => return Some(vec![render::text_block(&no_team(&team2))]),
+        };
+        Some(render::h2h(
+            render::league_color(&ffl_client.config.league_type),
+            &history::head_to_head(&history, &owner1, &owner2),
+        ))
+    }
+
+    async fn handle_draft(&self, ffl_client: &FflClient) -> Option<Vec<Reply>> {
+        let config = &ffl_client.config;
+        let color = render::league_color(&config.league_type);
+        let draft = match ffl_client.client().get_draft().await {
+            Ok(Some(d)) => d,
+            Ok(None) => return Some(vec![render::text_block("There's no draft this season.")]),
+            Err(e) => {
+                println!("failed to fetch draft: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch the draft, try again later.",
+                )]);
+            }
+        };
+        let mut replies = render::draft(&config.league_name, color, &draft);
+        if draft.status != DraftStatus::Complete {
+            return Some(replies);
+        }
+        // grades wait for the regular season to end, and the picks still go out without them
+        match draft_grades(ffl_client, &draft).await {
+            Ok(Some(grades)) => {
+                replies.extend(render::draft_grades(&config.league_name, color, &grades))
+            }
+            Ok(None) => {}
+            Err(e) => println!("failed to grade draft: {}", e),
+        }
+        Some(replies)
+    }
+
+    async fn handle_board(
+        &self,
+        ffl_client: &FflClient,
+        position: Option<String>,
+    ) -> Option<Vec<Reply>> {
+        let config = &ffl_client.config;
+        let draft = match ffl_client.client().get_draft().await {
+            Ok(d) => d,
+            Err(e) => {
+                println!("failed to fetch draft: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch the draft, try again later.",
+                )]);
+            }
+        };
+        let board = match draft_board(ffl_client).await {
+            Ok(b) => b,
+            Err(e) => {
+                println!("failed to build draft board: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't build the draft board, try again later.",
+                )]);
+            }
+        };
+        let picks = draft.as_ref().map_or(&[][..], |d| &d.picks[..]);
+        let position = position.as_deref().map(waivers::normalize_position);
+        let available: Vec<&PlayerProjection> = draft::best_available(&board, picks)
+            .into_iter()
+            .filter(|p| {
+                position
+                    .as_ref()
+                    .is_none_or(|pos| &p.player.position == pos)
+            })
+            .collect();
+        Some(render::board(
+            &config.league_name,
+            render::league_color(&config.league_type),
+            position.as_deref(),
+            config.draft_rankings.is_some(),
+            &available,
+        ))
+    }
+
+    async fn handle_keepers(&self, ffl_client: &FflClient) -> Option<Vec<Reply>> {
+        let config = &ffl_client.config;
+        let rules = match &config.keepers {
+            Some(r) => r,
+            None => return Some(vec![render::text_block("This isn't a keeper league.")]),
+        };
+        let draft = match ffl_client.client().get_draft().await {
+            Ok(Some(d)) if d.status == DraftStatus::Complete => d,
+            Ok(_) => {
+                return Some(vec![render::text_block(
+                    "Keepers are priced from this season's draft, once it's done.",
+                )])
+            }
+            Err(e) => {
+                println!("failed to fetch draft: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch the draft, try again later.",
+                )]);
+            }
+        };
+        let teams = match keeper_options(ffl_client, rules, &draft).await {
+            Ok(t) => t,
+            Err(e) => {
+                println!("failed to work out keepers: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't work out keepers, try again later.",
+                )]);
+            }
+        };
+        Some(render::keepers(
+            &config.league_name,
+            render::league_color(&config.league_type),
+            rules,
+            &teams,
+        ))
+    }
+
+    async fn handle_settings(&self, ffl_client: &FflClient) -> Option<Vec<Reply>> {
+        let settings = match ffl_client.client().get_league_settings().await {
+            Ok(s) => s,
+            Err(e) => {
+                println!("failed to fetch league settings: {}", e);
+                return Some(vec![render::text_block(
+                    "Couldn't fetch the league's settings, try again later.",
+                )]);
+            }
+        };
+        Some(render::settings(
+            &ffl_client.config.league_name,
+            render::league_color(&ffl_client.config.league_type),
+            &settings,
+        ))
+    }
+
+    async fn handle_records(&self, ffl_client: &FflClient) -> Option<Vec<Reply>> {
+        let config = &ffl_client.config;
+        // the scheduler keeps the book current; it's only built here if it hasn't yet
+        let book = match records::stored(&self.storage, &config.league_id) {
+            Some(book) => book,
+            None => {
+                let book = history::update(ffl_client.client(), &self.storage, &config.league_id)
+                    .await
+                    .and_then(|history| {
+                        records::update(&self.storage, &config.league_id, &history)
+                    });
+                match book {
+                    Ok((book, _)) => book,
+                    Err(e) => {
+                        println!("failed to update the record book: {}", e);
+                        return Some(vec![render::text_block(
+                            "Couldn't fetch the league's history, try again later.",
+                        )]);
+                    }
+                }
+            }
+        };
+        Some(render::records(
+            &config.league_name,
+            render::league_color(&config.league_type),
+            &book,
+        ))
+    }
+
+    async fn handle_power(&self, ffl_client: &FflClient) -> Option<Vec<Reply>> {
+        println!(
+            "getting power for league {} of type {:?}",
+            ffl_client.config.league_id, ffl_client.config.league_type
+        );
+        let power =
+            match get_power_rankings(&self.power_ranking_url_format, &ffl_client.config).await {
+                Ok(p) => p,
+                Err(e) => {
+                    println!("failed to fetch power rankings: {}", e);
+                    return Some(vec![render::text_block(
+                        "Couldn't fetch power rankings, try again later.",
+                    )]);
+                }
+            };
+
+        match power {
+            None => Some(vec![Reply::Text(
+                "power rankings for sleeper not implemented yet sorryyyyyyyy".to_string(),
+            )]),
+            Some(power) => Some(render::power(
+                &ffl_client.config.league_name,
+                render::league_color(&ffl_client.config.league_type),
+                &power,
+            )),
+        }
+    }
+}
+
+// Simulated playoff odds for every team, and whether the league gives out byes.
+async fn playoff_chances(
+    ffl_client: &FflClient,
+) -> Result<(bool, Vec<PlayoffOdds>), Box<dyn Error>> {
+    let client = ffl_client.client();
+    let format = client.get_playoff_format().await?;
+    let standings = client.get_standings().await?;
+    let schedule = client.get_schedule().await?;
+    let played = schedule.played_matchups();
+    let remaining = schedule.remaining_matchups();
+    let teams: Vec<FantasyTeam> = standings.iter().map(|s| s.team.clone()).collect();
+    let scoring = playoffs::scoring(&teams, &played);
+    let season = Season {
+        standings: &standings,
+        played: &played,
+        remaining: &remaining,
+        scoring: &scoring,
+        format: &format,
+    };
+    // seeded by how far into the season it is, so everyone asking during a week sees the same
+    // numbers
+    let weeks_played = schedule.weeks.iter().filter(|w| w.played).count();
+    let odds = playoffs::simulate(&season, playoffs::SIMULATIONS, weeks_played as u64);
+    Ok((format.byes > 0, odds))
+}
+
+// Lineup issues for every team in a league, along with the week they're for.
+async fn lineup_checks(
+    ffl_client: &FflClient,
+    storage: &Storage,
+) -> Result<(u32, Vec<(FantasyTeam, Vec<LineupIssue>)>), Box<dyn Error>> {
+    let state = get_nfl_state().await?;
+    let byes = nfl::get_bye_weeks(storage, &state.season).await?;
+    let rosters = ffl_client.client().get_rosters().await?;
+    let checks = rosters
+        .iter()
+        .map(|r| (r.team.clone(), lineup::check(r, state.week, &byes)))
+        .collect();
+    Ok((state.week, checks))
+}
+
+// Projections and game info for each named player, or None for names that didn't match.
+async fn player_outlooks(
+    ffl_client: &FflClient,
+    names: &[String],
+) -> Result<(u32, Vec<Option<PlayerOutlook>>), Box<dyn Error>> {
+    let state = get_nfl_state().await?;
+    let games = nfl::get_games(&state.season, state.week).await?;
+    let projections = ffl_client
+        .client()
+        .search_players(names, state.week)
+        .await?;
+    let outlooks = projections
+        .into_iter()
+        .map(|p| p.map(|p| PlayerOutlook::new(p, &games, chrono::Utc::now())))
+        .collect();
+    Ok((state.week, outlooks))
+}
+
+// Every free agent for the current week, plus what's left of each team's FAAB budget and how
+// many of the league's weeks are left, this one included.
+async fn free_agents(
+    ffl_client: &FflClient,
+) -> Result<(u32, Vec<PlayerProjection>, Vec<FaabBalance>), Box<dyn Error>> {
+    let state = get_nfl_state().await?;
+    let client = ffl_client.client();
+    let settings = client.get_league_settings().await?;
+    let free_agents = client.get_free_agents(state.week).await?;
+    let faab = client.get_faab_balances().await?;
+    let weeks_left = (settings.last_week() + 1).saturating_sub(state.week);
+    Ok((weeks_left, free_agents, faab))
+}
+
+// Grades for a draft once the regular season is over, or None before then.
+async fn draft_grades(
+    ffl_client: &FflClient,
+    draft: &Draft,
+) -> Result<Option<draft::DraftGrades>, Box<dyn Error>> {
+    let client = ffl_client.client();
+    let schedule = client.get_schedule().await?;
+    if !schedule.remaining_matchups().is_empty() {
+        return Ok(None);
+    }
+    let player_ids: Vec<String> = draft.picks.iter().map(|p| p.player.id.clone()).collect();
+    let points = client.get_season_points(&player_ids).await?;
+    Ok(Some(draft::grade(&draft.picks, &points)))
+}
+
+// The league's draft board, drafted players included: its imported rank list if it has one, or
+// projections under its scoring if not.  Either way it only has positions the league starts.
+async fn draft_board(ffl_client: &FflClient) -> Result<Vec<PlayerProjection>, Box<dyn Error>> {
+    let client = ffl_client.client();
+    let settings = client.get_league_settings().await?;
+    let week_num = get_nfl_state().await?.week.max(1);
+    let mut board = match &ffl_client.config.draft_rankings {
+        Some(path) => {
+            let rankings = draft::parse_rankings(&fs::read_to_string(path)?)?;
+            let names: Vec<String> = rankings.iter().map(|r| r.name.clone()).collect();
+            let found = client.search_players(&names, week_num).await?;
+            // a name that matched somebody at another position matched the wrong player
+            found
+                .into_iter()
+                .zip(rankings.iter())
+                .filter_map(|(player, ranking)| {
+                    player.filter(|p| {
+                        ranking.position.as_deref().is_none_or(|position| {
+                            waivers::normalize_position(position) == p.player.position
+                        })
+                    })
+                })
+                .collect()
+        }
+        None => {
+            let mut free_agents = client.get_free_agents(week_num).await?;
+            draft::sort_by_projection(&mut free_agents);
+            free_agents
+        }
+    };
+    let positions = draft::startable_positions(&settings.roster_positions);
+    let mut seen = HashSet::new();
+    board.retain(|p| {
+        positions.contains(p.player.position.as_str()) && seen.insert(p.player.id.clone())
+    });
+    Ok(board)
+}
+
+// Every team's keeper options for next season, from this season's finished draft.
+async fn keeper_options(
+    ffl_client: &FflClient,
+    rules: &KeeperRules,
+    draft: &Draft,
+) -> Result<Vec<TeamKeepers>, Box<dyn Error>> {
+    let client = ffl_client.client();
+    let settings = client.get_league_settings().await?;
+    let rosters = client.get_rosters().await?;
+    let traded = client.get_traded_picks().await?;
+    let player_ids: Vec<String> = draft
+        .picks
+        .iter()
+        .map(|p| p.player.id.clone())
+        .chain(
+            rosters
+                .iter()
+                .flat_map(|r| r.players().map(|p| p.id.clone())),
+        )
+        .collect();
+    let points = client.get_season_points(&player_ids).await?;
+    let next_season = (settings.season.parse::<u32>()? + 1).to_string();
+    Ok(keepers::eligible(
+        rules,
+        draft,
+        &rosters,
+        &points,
+        &traded,
+        &next_season,
+    ))
+}
+
+fn no_team(name: &str) -> String {
+    format!("Couldn't find a team or owner called \"{}\".", name)
+}
+
+// The value of a string option, if the user filled it in.
+fn get_option(command: &ApplicationCommandInteraction, name: &str) -> Option<String> {
+    command
+        .data
+        .options
+        .iter()
+        .find(|o| o.name == name)
+        .and_then(|o| o.value.as_ref())
+        .and_then(|v| v.as_str())
+        .map(|v| v.to_owned())
+}
+
+fn set_reply<'a>(
+    message: &'a mut CreateInteractionResponseData,
+    reply: &Reply,
+) -> &'a mut CreateInteractionResponseData {
+    match reply {
+        Reply::Text(t) => message.content(t).embeds(vec![]),
+        Reply::Embeds(embeds) => message.content("").embeds(embeds.iter().map(create_embed)),
+    }
+}
+
+fn edit_reply<'a>(
+    response: &'a mut EditInteractionResponse,
+    reply: &Reply,
+) -> &'a mut EditInteractionResponse {
+    match reply {
+        Reply::Text(t) => response.content(t).set_embeds(vec![]),
+        Reply::Embeds(embeds) => response
+            .content("")
+            .set_embeds(embeds.iter().map(create_embed).collect()),
+    }
+}
+
+fn create_embed(embed: &render::Embed) -> CreateEmbed {
+    let mut e = CreateEmbed::default();
+    e.title(&embed.title).color(embed.color);
+    if let Some(d) = &embed.description {
+        e.description(d);
+    }
+    if let Some(t) = &embed.thumbnail {
+        e.thumbnail(t);
+    }
+    for f in embed.fields.iter() {
+        e.field(&f.name, &f.value, f.inline);
+    }
+    if let Some(f) = &embed.footer {
+        e.footer(|footer| footer.text(f));
     }
+    e
 }
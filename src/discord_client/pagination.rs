@@ -0,0 +1,150 @@
+use super::super::render::Reply;
+use serenity::{
+    builder::CreateComponents, model::id::MessageId,
+    model::interactions::message_component::ButtonStyle,
+};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub const PAGE_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const PREV_ID: &str = "page_prev";
+const NEXT_ID: &str = "page_next";
+const PAGE_COUNT_ID: &str = "page_count";
+
+struct PageState {
+    pages: Vec<Reply>,
+    current: usize,
+    created: Instant,
+}
+
+// Pages for every paginated message we've sent, keyed by message ID.
+#[derive(Default)]
+pub struct Paginator {
+    state: Mutex<HashMap<MessageId, PageState>>,
+}
+
+impl Paginator {
+    pub fn insert(&self, message_id: MessageId, pages: Vec<Reply>) {
+        let mut state = self.state.lock().unwrap();
+        state.retain(|_, s| s.created.elapsed() < PAGE_TIMEOUT);
+        state.insert(
+            message_id,
+            PageState {
+                pages,
+                current: 0,
+                created: Instant::now(),
+            },
+        );
+    }
+
+    pub fn remove(&self, message_id: &MessageId) {
+        self.state.lock().unwrap().remove(message_id);
+    }
+
+    pub fn is_page_button(custom_id: &str) -> bool {
+        custom_id == PREV_ID || custom_id == NEXT_ID
+    }
+
+    // Moves to the previous or next page and returns it along with its index and the page
+    // count, or None if we don't know about the message anymore.
+    pub fn turn(&self, message_id: &MessageId, custom_id: &str) -> Option<(Reply, usize, usize)> {
+        let mut state = self.state.lock().unwrap();
+        let expired = state
+            .get(message_id)
+            .is_some_and(|s| s.created.elapsed() >= PAGE_TIMEOUT);
+        if expired {
+            state.remove(message_id);
+            return None;
+        }
+        let s = state.get_mut(message_id)?;
+        if custom_id == PREV_ID {
+            s.current = s.current.saturating_sub(1);
+        } else if custom_id == NEXT_ID {
+            s.current = (s.current + 1).min(s.pages.len() - 1);
+        }
+        Some((s.pages[s.current].clone(), s.current, s.pages.len()))
+    }
+}
+
+pub fn page_buttons(
+    components: &mut CreateComponents,
+    current: usize,
+    total: usize,
+) -> &mut CreateComponents {
+    components.create_action_row(|row| {
+        row.create_button(|b| {
+            b.style(ButtonStyle::Secondary)
+                .label("◀ Prev")
+                .custom_id(PREV_ID)
+                .disabled(current == 0)
+        })
+        .create_button(|b| {
+            b.style(ButtonStyle::Secondary)
+                .label(format!("{}/{}", current + 1, total))
+                .custom_id(PAGE_COUNT_ID)
+                .disabled(true)
+        })
+        .create_button(|b| {
+            b.style(ButtonStyle::Secondary)
+                .label("Next ▶")
+                .custom_id(NEXT_ID)
+                .disabled(current + 1 >= total)
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pages(count: usize) -> Vec<Reply> {
+        (0..count).map(|i| Reply::Text(i.to_string())).collect()
+    }
+
+    #[test]
+    fn turns_stop_at_both_ends() {
+        let paginator = Paginator::default();
+        let id = MessageId(1);
+        paginator.insert(id, pages(3));
+
+        let page =
+            |turn: Option<(Reply, usize, usize)>| turn.map(|(_, current, total)| (current, total));
+        assert_eq!(page(paginator.turn(&id, PREV_ID)), Some((0, 3)));
+        assert_eq!(page(paginator.turn(&id, NEXT_ID)), Some((1, 3)));
+        assert_eq!(page(paginator.turn(&id, NEXT_ID)), Some((2, 3)));
+        assert_eq!(page(paginator.turn(&id, NEXT_ID)), Some((2, 3)));
+        let (reply, _, _) = paginator.turn(&id, PREV_ID).unwrap();
+        assert_eq!(reply, Reply::Text("1".to_string()));
+    }
+
+    #[test]
+    fn unknown_and_expired_messages() {
+        let paginator = Paginator::default();
+        assert!(paginator.turn(&MessageId(1), NEXT_ID).is_none());
+
+        paginator.insert(MessageId(1), pages(2));
+        paginator
+            .state
+            .lock()
+            .unwrap()
+            .get_mut(&MessageId(1))
+            .unwrap()
+            .created = Instant::now() - PAGE_TIMEOUT;
+        assert!(paginator.turn(&MessageId(1), NEXT_ID).is_none());
+        assert!(paginator.state.lock().unwrap().is_empty());
+
+        // expired pages are cleared out whenever another message is paginated
+        paginator.insert(MessageId(2), pages(2));
+        paginator
+            .state
+            .lock()
+            .unwrap()
+            .get_mut(&MessageId(2))
+            .unwrap()
+            .created = Instant::now() - PAGE_TIMEOUT;
+        paginator.insert(MessageId(3), pages(2));
+        let state = paginator.state.lock().unwrap();
+        assert_eq!(state.keys().collect::<Vec<_>>(), [&MessageId(3)]);
+    }
+}
@@ -0,0 +1,540 @@
+use super::super::draft;
+use super::super::fantasy_client::sleeper::{
+    get_nfl_state, SleeperNflStateApiResponse, SleeperPlayers,
+};
+use super::super::fantasy_client::{
+    get_power_rankings, DraftPick, DraftStatus, FflClient, LeagueConfig, PowerRankings,
+};
+use super::super::history;
+use super::super::nfl;
+use super::super::preview;
+use super::super::recap::{self, WeekRecap};
+use super::super::records;
+use super::super::render::{self, Reply};
+use super::super::storage::{league_key, Storage};
+use super::{create_embed, draft_board, lineup_checks};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serenity::{
+    http::Http,
+    model::id::{ChannelId, UserId},
+};
+use std::error::Error;
+use std::sync::Arc;
+use std::time::Duration;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
+// how long before the week's first kickoff to post previews; must be longer than POLL_INTERVAL
+const PREVIEW_LEAD_HOURS: i64 = 6;
+// picks are posted this often while a draft is underway
+const DRAFT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+// and this is how often we look for one starting
+const DRAFT_IDLE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+// the last week we posted something for, so restarts don't post it again
+#[derive(Deserialize, Serialize, Debug)]
+struct PostState {
+    season: String,
+    last_week: u32,
+}
+
+// the last pick we posted from a league's draft
+#[derive(Deserialize, Serialize, Debug)]
+struct DraftState {
+    draft_id: String,
+    last_pick: u32,
+    // every pick has been posted, so there's nothing left to watch
+    #[serde(default)]
+    complete: bool,
+}
+
+// Background jobs that post to league channels on their own schedule.
+pub struct Scheduler {
+    pub http: Arc<Http>,
+    pub ffl_clients: Arc<Vec<FflClient>>,
+    pub storage: Arc<Storage>,
+    pub power_ranking_url_format: String,
+    pub sleeper_players: Arc<SleeperPlayers>,
+}
+
+impl Scheduler {
+    pub async fn run(self) {
+        tokio::join!(self.run_jobs(), self.track_drafts());
+    }
+
+    async fn run_jobs(&self) {
+        loop {
+            if let Err(e) = self.sleeper_players.refresh().await {
+                println!("failed to refresh Sleeper players: {}", e);
+            }
+            let state = match get_nfl_state().await {
+                Ok(s) => Some(s),
+                Err(e) => {
+                    println!("failed to get NFL state: {}", e);
+                    None
+                }
+            };
+            if let Some(state) = state {
+                if let Err(e) = self.check_recaps(&state).await {
+                    println!("failed to check for recaps: {}", e);
+                }
+                if let Err(e) = self.check_previews(&state).await {
+                    println!("failed to check for previews: {}", e);
+                }
+                if let Err(e) = self.check_lineups(&state).await {
+                    println!("failed to check lineups: {}", e);
+                }
+            }
+            self.update_history().await;
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    // Sleeper moves on to the next week once the last game of the current one is over, so a
+    // new week number means the previous week is done scoring.  After the last week, it moves
+    // on to the postseason instead.
+    async fn check_recaps(&self, state: &SleeperNflStateApiResponse) -> Result<(), Box<dyn Error>> {
+        let finished_week = match state.season_type.as_str() {
+            "regular" => state.week.saturating_sub(1),
+            "post" => nfl::REGULAR_SEASON_WEEKS,
+            _ => return Ok(()),
+        };
+        if finished_week == 0 {
+            return Ok(());
+        }
+
+        for ffl_client in self.ffl_clients.iter() {
+            let channel_id = match league_channel(&ffl_client.config) {
+                Some(c) => c,
+                None => continue,
+            };
+            let key = league_key(&ffl_client.config.league_id, "recap");
+            let recap_state: Option<PostState> = self.storage.load(&key);
+            let new_state = PostState {
+                season: state.season.clone(),
+                last_week: finished_week,
+            };
+            match recap_state {
+                // don't spam a recap for whatever week it happens to be the first time we run
+                None => {
+                    self.storage.save(&key, &new_state)?;
+                    continue;
+                }
+                Some(s) if s.season == state.season && s.last_week >= finished_week => continue,
+                Some(_) => {}
+            }
+
+            match self
+                .post_recap(ffl_client, channel_id, &state.season, finished_week)
+                .await
+            {
+                Ok(()) => self.storage.save(&key, &new_state)?,
+                Err(e) => println!(
+                    "failed to post recap for league {}: {}",
+                    ffl_client.config.league_name, e
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    async fn post_recap(
+        &self,
+        ffl_client: &FflClient,
+        channel_id: ChannelId,
+        season: &str,
+        week_num: u32,
+    ) -> Result<(), Box<dyn Error>> {
+        let config = &ffl_client.config;
+        let client = ffl_client.client();
+        let matchups = client.get_matchups(Some(week_num)).await?;
+        // the league's season ended before the NFL's did
+        if matchups.is_empty() {
+            return Ok(());
+        }
+        let lineups = client.get_lineups(week_num).await?;
+
+        // the recap still goes out without power rankings
+        let power = match get_power_rankings(&self.power_ranking_url_format, config).await {
+            Ok(p) => p,
+            Err(e) => {
+                println!(
+                    "failed to get power rankings for league {}: {}",
+                    config.league_name, e
+                );
+                None
+            }
+        };
+        if let Some(p) = &power {
+            let key = league_key(&config.league_id, &format!("power/{}/{}", season, week_num));
+            self.storage.save(&key, p)?;
+        }
+        let previous_key = league_key(
+            &config.league_id,
+            &format!("power/{}/{}", season, week_num - 1),
+        );
+        let previous_power: Option<PowerRankings> = self.storage.load(&previous_key);
+
+        let week_recap = WeekRecap::new(
+            week_num,
+            &matchups,
+            &lineups,
+            previous_power.as_ref(),
+            power.as_ref(),
+        );
+        let template = config
+            .recap_template
+            .as_deref()
+            .unwrap_or(recap::DEFAULT_TEMPLATE);
+        let mut text = week_recap.render(&config.league_name, template);
+        // the recap still goes out if the record book can't be updated
+        match history::update(client, &self.storage, &config.league_id)
+            .await
+            .and_then(|history| records::update(&self.storage, &config.league_id, &history))
+        {
+            Ok((_, new_records)) if !new_records.is_empty() => {
+                text = format!("{}\n\n{}", text, render::new_records(&new_records));
+            }
+            Ok(_) => {}
+            Err(e) => println!(
+                "failed to update records for league {}: {}",
+                config.league_name, e
+            ),
+        }
+        for chunk in render::split_message(&text, render::MESSAGE_LIMIT) {
+            channel_id.say(&self.http, chunk).await?;
+        }
+        Ok(())
+    }
+
+    // Keeps each league's stored history and record book current, so commands that need all
+    // of it don't have to fetch it.  Leagues with recaps get their new records announced there,
+    // so their book is only started here.
+    async fn update_history(&self) {
+        for ffl_client in self.ffl_clients.iter() {
+            let config = &ffl_client.config;
+            let history = match history::update(
+                ffl_client.client(),
+                &self.storage,
+                &config.league_id,
+            )
+            .await
+            {
+                Ok(h) => h,
+                Err(e) => {
+                    println!(
+                        "failed to update history for league {}: {}",
+                        config.league_name, e
+                    );
+                    continue;
+                }
+            };
+            if config.discord_channel_id.is_some()
+                && records::stored(&self.storage, &config.league_id).is_some()
+            {
+                continue;
+            }
+            if let Err(e) = records::update(&self.storage, &config.league_id, &history) {
+                println!(
+                    "failed to update records for league {}: {}",
+                    config.league_name, e
+                );
+            }
+        }
+    }
+
+    // Previews go out once per week, shortly before the first game kicks off.
+    async fn check_previews(
+        &self,
+        state: &SleeperNflStateApiResponse,
+    ) -> Result<(), Box<dyn Error>> {
+        if state.season_type != "regular" {
+            return Ok(());
+        }
+        if !is_pregame(state).await? {
+            return Ok(());
+        }
+
+        for ffl_client in self.ffl_clients.iter() {
+            let channel_id = match league_channel(&ffl_client.config) {
+                Some(c) => c,
+                None => continue,
+            };
+            let key = league_key(&ffl_client.config.league_id, "preview");
+            let preview_state: Option<PostState> = self.storage.load(&key);
+            if let Some(s) = preview_state {
+                if s.season == state.season && s.last_week >= state.week {
+                    continue;
+                }
+            }
+
+            match self.post_preview(ffl_client, channel_id, state.week).await {
+                Ok(()) => self.storage.save(
+                    &key,
+                    &PostState {
+                        season: state.season.clone(),
+                        last_week: state.week,
+                    },
+                )?,
+                Err(e) => println!(
+                    "failed to post preview for league {}: {}",
+                    ffl_client.config.league_name, e
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    async fn post_preview(
+        &self,
+        ffl_client: &FflClient,
+        channel_id: ChannelId,
+        week_num: u32,
+    ) -> Result<(), Box<dyn Error>> {
+        let config = &ffl_client.config;
+        let client = ffl_client.client();
+        let matchups = client.get_matchups(Some(week_num)).await?;
+        let lineups = client.get_lineups(week_num).await?;
+        let standings = client.get_standings().await?;
+        // the scheduler keeps the stored copy current, so it's only fetched here the first time
+        let history = match history::stored(&self.storage, &config.league_id) {
+            Some(h) => h,
+            None => history::update(client, &self.storage, &config.league_id).await?,
+        };
+
+        let previews = preview::build(&matchups, &lineups, &standings, &history);
+        let pages = render::previews(
+            &config.league_name,
+            render::league_color(&config.league_type),
+            week_num,
+            &previews,
+        );
+        self.send_pages(channel_id, pages).await
+    }
+
+    // DMs owners who've linked their Discord account about problems with their starting
+    // lineup, once per week before the first kickoff.
+    async fn check_lineups(
+        &self,
+        state: &SleeperNflStateApiResponse,
+    ) -> Result<(), Box<dyn Error>> {
+        if state.season_type != "regular" || !is_pregame(state).await? {
+            return Ok(());
+        }
+
+        for ffl_client in self.ffl_clients.iter() {
+            let config = &ffl_client.config;
+            if !config.lineup_dms || config.discord_owners.is_empty() {
+                continue;
+            }
+            let key = league_key(&config.league_id, "lineup_dm");
+            let dm_state: Option<PostState> = self.storage.load(&key);
+            if let Some(s) = dm_state {
+                if s.season == state.season && s.last_week >= state.week {
+                    continue;
+                }
+            }
+
+            let checks = match lineup_checks(ffl_client, &self.storage).await {
+                Ok((_, c)) => c,
+                Err(e) => {
+                    println!(
+                        "failed to check lineups for league {}: {}",
+                        config.league_name, e
+                    );
+                    continue;
+                }
+            };
+            // one owner we can't reach doesn't stop the rest, and nobody is DMed twice
+            for (team, issues) in checks {
+                if issues.is_empty() {
+                    continue;
+                }
+                let user = match config.discord_owners.get(&team.id) {
+                    Some(u) => u.clone(),
+                    None => continue,
+                };
+                let team_name = team.team_name.clone();
+                let pages = render::lineup_check(state.week, &[(team, issues)]);
+                if let Err(e) = self.send_dm(&user, pages).await {
+                    println!(
+                        "failed to DM the owner of {} in league {}: {}",
+                        team_name, config.league_name, e
+                    );
+                }
+            }
+            self.storage.save(
+                &key,
+                &PostState {
+                    season: state.season.clone(),
+                    last_week: state.week,
+                },
+            )?;
+        }
+        Ok(())
+    }
+
+    // Drafts are checked every few minutes, and every few seconds while one is underway.
+    // Leagues whose draft is done or hasn't been set up yet keep getting checked at the slower
+    // pace, so next season's draft is picked up without a restart.
+    async fn track_drafts(&self) {
+        loop {
+            let mut drafting = false;
+            for ffl_client in self.ffl_clients.iter() {
+                match self.check_draft(ffl_client).await {
+                    Ok(status) => drafting |= status == Some(DraftStatus::Drafting),
+                    Err(e) => println!(
+                        "failed to check draft for league {}: {}",
+                        ffl_client.config.league_name, e
+                    ),
+                }
+            }
+            let interval = if drafting {
+                DRAFT_POLL_INTERVAL
+            } else {
+                DRAFT_IDLE_INTERVAL
+            };
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    // Posts every pick made since we last looked, returning where the draft is at, or None if
+    // there's no draft to post.
+    async fn check_draft(
+        &self,
+        ffl_client: &FflClient,
+    ) -> Result<Option<DraftStatus>, Box<dyn Error>> {
+        let config = &ffl_client.config;
+        let channel_id = match league_channel(config) {
+            Some(c) => c,
+            None => return Ok(None),
+        };
+        let client = ffl_client.client();
+        let draft = match client.get_draft().await? {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        let key = league_key(&config.league_id, "draft");
+        let draft_state: Option<DraftState> = self.storage.load(&key);
+        let last_pick = match draft_state {
+            Some(s) if s.draft_id == draft.id && s.complete => {
+                return Ok(Some(DraftStatus::Complete))
+            }
+            Some(s) if s.draft_id == draft.id => s.last_pick,
+            // don't spam a draft that was already going the first time we see it.  We see new
+            // drafts before they start, so this is normally 0.
+            _ => draft.picks.iter().map(|p| p.pick_no).max().unwrap_or(0),
+        };
+        self.storage.save(
+            &key,
+            &DraftState {
+                draft_id: draft.id.clone(),
+                last_pick,
+                complete: false,
+            },
+        )?;
+
+        let new_picks: Vec<&DraftPick> = draft
+            .picks
+            .iter()
+            .filter(|p| p.pick_no > last_pick)
+            .collect();
+        if !new_picks.is_empty() {
+            // the same board /board shows; picks still go out without it
+            let board = match draft_board(ffl_client).await {
+                Ok(b) => b,
+                Err(e) => {
+                    println!(
+                        "failed to get the draft board for league {}: {}",
+                        config.league_name, e
+                    );
+                    vec![]
+                }
+            };
+            for pick in new_picks {
+                // who was left right after this pick
+                let taken: Vec<DraftPick> = draft
+                    .picks
+                    .iter()
+                    .filter(|p| p.pick_no <= pick.pick_no)
+                    .cloned()
+                    .collect();
+                let available = draft::best_available(&board, &taken);
+                channel_id
+                    .say(&self.http, render::live_pick(pick, &available))
+                    .await?;
+                self.storage.save(
+                    &key,
+                    &DraftState {
+                        draft_id: draft.id.clone(),
+                        last_pick: pick.pick_no,
+                        complete: false,
+                    },
+                )?;
+            }
+        }
+        if draft.status == DraftStatus::Complete {
+            self.storage.save(
+                &key,
+                &DraftState {
+                    draft_id: draft.id.clone(),
+                    last_pick: draft.picks.iter().map(|p| p.pick_no).max().unwrap_or(0),
+                    complete: true,
+                },
+            )?;
+        }
+        Ok(Some(draft.status))
+    }
+
+    async fn send_dm(&self, user: &str, pages: Vec<Reply>) -> Result<(), Box<dyn Error>> {
+        let user_id = UserId(user.parse::<u64>()?);
+        let channel = user_id.create_dm_channel(&self.http).await?;
+        self.send_pages(channel.id, pages).await
+    }
+
+    async fn send_pages(
+        &self,
+        channel_id: ChannelId,
+        pages: Vec<Reply>,
+    ) -> Result<(), Box<dyn Error>> {
+        for page in pages {
+            match page {
+                Reply::Text(t) => channel_id.say(&self.http, t).await?,
+                Reply::Embeds(embeds) => {
+                    channel_id
+                        .send_message(&self.http, |m| {
+                            m.set_embeds(embeds.iter().map(create_embed).collect())
+                        })
+                        .await?
+                }
+            };
+        }
+        Ok(())
+    }
+}
+
+// The channel a league posts to, if it has one we can use.
+fn league_channel(config: &LeagueConfig) -> Option<ChannelId> {
+    let channel = config.discord_channel_id.as_ref()?;
+    match channel.parse::<u64>() {
+        Ok(id) => Some(ChannelId(id)),
+        Err(e) => {
+            println!(
+                "bad channel ID {} for league {}: {}",
+                channel, config.league_name, e
+            );
+            None
+        }
+    }
+}
+
+// Whether we're in the window shortly before the first game of the current week.
+async fn is_pregame(state: &SleeperNflStateApiResponse) -> Result<bool, Box<dyn Error>> {
+    let games = nfl::get_games(&state.season, state.week).await?;
+    let kickoff = match nfl::first_kickoff(&games) {
+        Some(k) => k,
+        None => return Ok(false),
+    };
+    let now = Utc::now();
+    Ok(now < kickoff && kickoff - now <= chrono::Duration::hours(PREVIEW_LEAD_HOURS))
+}
@@ -0,0 +1,328 @@
+use super::fantasy_client::{DraftPick, FantasyTeam, PlayerProjection};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+
+const GRADES: [&str; 5] = ["A", "B", "C", "D", "F"];
+
+// Column headers we recognize in an imported rank list, lowercase.  FantasyPros exports use
+// "rk", "player name" and "pos".
+const RANK_HEADERS: [&str; 2] = ["rk", "rank"];
+const NAME_HEADERS: [&str; 3] = ["player name", "player", "name"];
+const POSITION_HEADERS: [&str; 2] = ["pos", "position"];
+
+// A pick next to what its draft slot should have been worth.
+#[derive(Clone)]
+pub struct PickValue {
+    pub pick: DraftPick,
+    pub points: f64,
+    // what the player drafted in the same spot at the position scored: the 3rd QB taken is
+    // expected to have the 3rd best season of the QBs drafted
+    pub expected: f64,
+    pub surplus: f64,
+}
+
+pub struct TeamGrade {
+    pub team: FantasyTeam,
+    pub grade: &'static str,
+    pub surplus: f64,
+    // best first
+    pub picks: Vec<PickValue>,
+}
+
+pub struct DraftGrades {
+    // best first
+    pub teams: Vec<TeamGrade>,
+    // every pick, biggest steal first
+    pub picks: Vec<PickValue>,
+}
+
+// Grades a finished draft by how each pick's season compared to its slot.  Teams are graded on
+// a curve, so the league always has an A and an F.
+pub fn grade(picks: &[DraftPick], season_points: &HashMap<String, f64>) -> DraftGrades {
+    let points = |p: &DraftPick| season_points.get(&p.player.id).copied().unwrap_or(0.0);
+
+    let mut by_position: HashMap<&str, Vec<&DraftPick>> = HashMap::new();
+    for pick in picks.iter() {
+        by_position
+            .entry(&pick.player.position)
+            .or_default()
+            .push(pick);
+    }
+    let mut values = vec![];
+    for position_picks in by_position.values_mut() {
+        position_picks.sort_by_key(|p| p.pick_no);
+        let mut seasons: Vec<f64> = position_picks.iter().map(|p| points(p)).collect();
+        seasons.sort_by(|a, b| b.partial_cmp(a).unwrap());
+        for (pick, expected) in position_picks.iter().zip(seasons) {
+            values.push(PickValue {
+                pick: (*pick).clone(),
+                points: points(pick),
+                expected,
+                surplus: points(pick) - expected,
+            });
+        }
+    }
+    values.sort_by(|a, b| b.surplus.partial_cmp(&a.surplus).unwrap());
+
+    let mut teams: Vec<TeamGrade> = vec![];
+    for value in values.iter() {
+        let i = match teams.iter().position(|t| t.team.id == value.pick.team.id) {
+            Some(i) => i,
+            None => {
+                teams.push(TeamGrade {
+                    team: value.pick.team.clone(),
+                    grade: GRADES[0],
+                    surplus: 0.0,
+                    picks: vec![],
+                });
+                teams.len() - 1
+            }
+        };
+        teams[i].surplus += value.surplus;
+        teams[i].picks.push(value.clone());
+    }
+    teams.sort_by(|a, b| b.surplus.partial_cmp(&a.surplus).unwrap());
+    let n = teams.len().max(1);
+    for (i, team) in teams.iter_mut().enumerate() {
+        team.grade = GRADES[i * GRADES.len() / n];
+    }
+    DraftGrades {
+        teams,
+        picks: values,
+    }
+}
+
+// One row of an imported rank list.
+#[derive(Debug, PartialEq)]
+pub struct Ranking {
+    pub rank: u32,
+    pub name: String,
+    pub position: Option<String>,
+}
+
+// Splits a CSV line on commas outside of quotes.
+fn csv_fields(line: &str) -> Vec<String> {
+    let mut fields = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields.iter().map(|f| f.trim().to_string()).collect()
+}
+
+// Reads a rank list from a CSV with a header row.  It needs a player name column; rank and
+// position columns are used if they're there, and rows are ranked in order if not.
+pub fn parse_rankings(csv: &str) -> Result<Vec<Ranking>, Box<dyn Error>> {
+    let mut lines = csv.lines().filter(|l| !l.trim().is_empty());
+    let header: Vec<String> = csv_fields(lines.next().ok_or("the rank list is empty")?)
+        .iter()
+        .map(|h| h.to_lowercase())
+        .collect();
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|n| header.iter().position(|h| h == n))
+    };
+    let name_column = column(&NAME_HEADERS).ok_or("the rank list has no player name column")?;
+    let rank_column = column(&RANK_HEADERS);
+    let position_column = column(&POSITION_HEADERS);
+
+    let mut rankings = vec![];
+    for (i, line) in lines.enumerate() {
+        let fields = csv_fields(line);
+        let name = match fields.get(name_column) {
+            Some(n) if !n.is_empty() => n.clone(),
+            _ => continue,
+        };
+        let rank = rank_column
+            .and_then(|c| fields.get(c)?.parse::<u32>().ok())
+            .unwrap_or(i as u32 + 1);
+        // positional ranks like "RB12" are just the position to us
+        let position = position_column
+            .and_then(|c| fields.get(c))
+            .map(|p| p.trim_end_matches(|c: char| c.is_ascii_digit()).to_string())
+            .filter(|p| !p.is_empty());
+        rankings.push(Ranking {
+            rank,
+            name,
+            position,
+        });
+    }
+    rankings.sort_by_key(|r| r.rank);
+    Ok(rankings)
+}
+
+// The player positions that can fill a roster slot.
+fn slot_positions(slot: &str) -> &'static [&'static str] {
+    match slot {
+        "QB" => &["QB"],
+        "RB" => &["RB"],
+        "WR" => &["WR"],
+        "TE" => &["TE"],
+        "K" => &["K"],
+        "DEF" => &["DEF"],
+        "DL" => &["DL"],
+        "LB" => &["LB"],
+        "DB" => &["DB"],
+        "FLEX" => &["RB", "WR", "TE"],
+        "WRRB_FLEX" | "RB/WR" => &["RB", "WR"],
+        "REC_FLEX" | "WR/TE" => &["WR", "TE"],
+        "SUPER_FLEX" | "OP" => &["QB", "RB", "WR", "TE"],
+        "IDP_FLEX" => &["DL", "LB", "DB"],
+        // bench, IR and taxi spots don't make anyone worth drafting
+        _ => &[],
+    }
+}
+
+// Every position the league's starting lineup has room for.
+pub fn startable_positions(roster_positions: &[String]) -> HashSet<&'static str> {
+    roster_positions
+        .iter()
+        .flat_map(|slot| slot_positions(slot).iter().copied())
+        .collect()
+}
+
+// Puts the best rest of season projections first, for boards without a rank list.
+pub fn sort_by_projection(players: &mut [PlayerProjection]) {
+    players.sort_by(|a, b| {
+        b.rest_of_season
+            .unwrap_or(0.0)
+            .partial_cmp(&a.rest_of_season.unwrap_or(0.0))
+            .unwrap()
+    });
+}
+
+// Everybody on the board who hasn't been drafted, in board order.
+pub fn best_available<'a>(
+    board: &'a [PlayerProjection],
+    picks: &[DraftPick],
+) -> Vec<&'a PlayerProjection> {
+    let drafted: HashSet<&str> = picks.iter().map(|p| p.player.id.as_str()).collect();
+    board
+        .iter()
+        .filter(|p| !drafted.contains(p.player.id.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::FantasyPlayer;
+
+    fn pick(pick_no: u32, team: &str, player: &str, position: &str) -> DraftPick {
+        DraftPick {
+            pick_no,
+            round: 1,
+            team: FantasyTeam::test(team),
+            player: FantasyPlayer {
+                id: player.to_string(),
+                name: player.to_string(),
+                position: position.to_string(),
+                nfl_team: None,
+                injury_status: None,
+            },
+            keeper: false,
+        }
+    }
+
+    #[test]
+    fn steals_and_busts_by_position() {
+        let picks = vec![
+            pick(1, "A", "rb1", "RB"),
+            pick(2, "B", "rb2", "RB"),
+            pick(3, "B", "qb1", "QB"),
+            pick(4, "A", "rb3", "RB"),
+        ];
+        let points: HashMap<String, f64> = [("rb1", 150.0), ("rb2", 200.0), ("rb3", 180.0)]
+            .iter()
+            .map(|(id, p)| (id.to_string(), *p))
+            .collect();
+        let grades = grade(&picks, &points);
+
+        // the first RB taken had the worst RB season
+        let steal = &grades.picks[0];
+        assert_eq!(steal.pick.player.id, "rb3");
+        assert_eq!(steal.expected, 150.0);
+        assert_eq!(steal.surplus, 30.0);
+        let bust = grades.picks.last().unwrap();
+        assert_eq!(bust.pick.player.id, "rb1");
+        assert_eq!(bust.expected, 200.0);
+        assert_eq!(bust.surplus, -50.0);
+        // the only QB matched its slot, even without scoring
+        assert!(grades
+            .picks
+            .iter()
+            .any(|p| p.pick.player.id == "qb1" && p.surplus == 0.0));
+
+        assert_eq!(grades.teams[0].team.id, "B");
+        assert_eq!(grades.teams[0].grade, "A");
+        assert_eq!(grades.teams[1].grade, "C");
+    }
+
+    #[test]
+    fn best_available_skips_drafted_players() {
+        let projection = |player: &str, rest_of_season: Option<f64>| PlayerProjection {
+            player: pick(0, "", player, "WR").player,
+            projected: None,
+            recent: vec![],
+            rest_of_season,
+            season_points: None,
+        };
+        let mut board = vec![
+            projection("wr1", Some(200.0)),
+            projection("wr2", None),
+            projection("wr3", Some(250.0)),
+            projection("wr4", Some(150.0)),
+        ];
+        sort_by_projection(&mut board);
+        let available = best_available(&board, &[pick(1, "A", "wr3", "WR")]);
+        let ids: Vec<&str> = available.iter().map(|p| p.player.id.as_str()).collect();
+        assert_eq!(ids, vec!["wr1", "wr4", "wr2"]);
+    }
+
+    #[test]
+    fn imports_rankings_and_starting_positions() {
+        let csv = "\"RK\",TIERS,\"PLAYER NAME\",TEAM,\"POS\"\n\
+                   \"2\",\"1\",\"Ja'Marr Chase\",\"CIN\",\"WR1\"\n\
+                   \"1\",\"1\",\"Bijan Robinson\",\"ATL\",\"RB1\"\n\
+                   \n\
+                   \"3\",\"2\",\"Smith, Jr.\",\"\",\"\"\n";
+        let rankings = parse_rankings(csv).unwrap();
+        assert_eq!(
+            rankings[0],
+            Ranking {
+                rank: 1,
+                name: "Bijan Robinson".to_string(),
+                position: Some("RB".to_string()),
+            }
+        );
+        assert_eq!(rankings[1].name, "Ja'Marr Chase");
+        assert_eq!(rankings[2].name, "Smith, Jr.");
+        assert_eq!(rankings[2].position, None);
+
+        // no rank column ranks rows in order
+        let rankings = parse_rankings("name,pos\nJosh Allen,QB\nSaquon Barkley,RB\n").unwrap();
+        assert_eq!(rankings[1].rank, 2);
+        assert!(parse_rankings("rank,team\n1,KC\n").is_err());
+
+        let slots: Vec<String> = ["QB", "RB", "WR", "FLEX", "BN", "IR"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let positions = startable_positions(&slots);
+        assert!(positions.contains("TE"));
+        assert!(!positions.contains("K"));
+        assert!(!positions.contains("DEF"));
+    }
+}
@@ -1,17 +1,144 @@
+use super::super::players;
 use async_trait::async_trait;
+use chrono::DateTime;
 use http::{header::COOKIE, HeaderMap, HeaderValue};
+use phf::phf_map;
 use reqwest;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::error::Error;
 use std::option::Option;
 
-// TODO: doesn't support older seasons.  but the present is all that matters
 const ESPN_API_URL: &str = "https://fantasy.espn.com/apis/v3/games/ffl/seasons";
 const LEAGUE_API_PATH: &str = "segments/0/leagues";
+// seasons before 2018 are only in the league history, which sends back a list of one league
+const ESPN_HISTORY_URL: &str = "https://fantasy.espn.com/apis/v3/games/ffl/leagueHistory";
+const FIRST_CURRENT_SEASON: u32 = 2018;
+
+static POSITION_MAP: phf::Map<u32, &str> = phf_map! {
+    1u32 => "QB",
+    2u32 => "RB",
+    3u32 => "WR",
+    4u32 => "TE",
+    5u32 => "K",
+    16u32 => "DEF",
+};
+
+static PRO_TEAM_MAP: phf::Map<u32, &str> = phf_map! {
+    1u32 => "ATL", 2u32 => "BUF", 3u32 => "CHI", 4u32 => "CIN", 5u32 => "CLE",
+    6u32 => "DAL", 7u32 => "DEN", 8u32 => "DET", 9u32 => "GB", 10u32 => "TEN",
+    11u32 => "IND", 12u32 => "KC", 13u32 => "LV", 14u32 => "LAR", 15u32 => "MIA",
+    16u32 => "MIN", 17u32 => "NE", 18u32 => "NO", 19u32 => "NYG", 20u32 => "NYJ",
+    21u32 => "PHI", 22u32 => "ARI", 23u32 => "PIT", 24u32 => "LAC", 25u32 => "SF",
+    26u32 => "SEA", 27u32 => "TB", 28u32 => "WAS", 29u32 => "CAR", 30u32 => "JAX",
+    33u32 => "BAL", 34u32 => "HOU",
+};
+
+// lineup slot IDs, which aren't the same as position IDs
+static LINEUP_SLOT_MAP: phf::Map<u32, &str> = phf_map! {
+    0u32 => "QB", 2u32 => "RB", 3u32 => "RB/WR", 4u32 => "WR", 5u32 => "WR/TE",
+    6u32 => "TE", 7u32 => "OP", 16u32 => "DEF", 17u32 => "K", 23u32 => "FLEX",
+};
+
+// scoring stat IDs, named the way Sleeper names them
+static SCORING_STAT_MAP: phf::Map<u32, &str> = phf_map! {
+    3u32 => "pass_yd", 4u32 => "pass_td", 19u32 => "pass_2pt", 20u32 => "pass_int",
+    24u32 => "rush_yd", 25u32 => "rush_td", 26u32 => "rush_2pt", 42u32 => "rec_yd",
+    43u32 => "rec_td", 44u32 => "rec_2pt", 53u32 => "rec", 72u32 => "fum_lost",
+    85u32 => "fgmiss", 86u32 => "xpm", 88u32 => "xpmiss", 95u32 => "int",
+    96u32 => "fum_rec", 97u32 => "blk_kick", 98u32 => "safe", 99u32 => "sack",
+};
+
+// stat lines with this source are projections rather than actual stats
+const PROJECTED_STAT_SOURCE_ID: u32 = 1;
+
+// actual stats, as opposed to projections
+const ACTUAL_STAT_SOURCE_ID: u32 = 0;
+// how many of the most-owned players to search through
+const PLAYER_SEARCH_LIMIT: u32 = 1000;
+
+// lineup slots that don't count as starting
+const BENCH_SLOT_ID: u32 = 20;
+const IR_SLOT_ID: u32 = 21;
 
 #[derive(Deserialize, Debug)]
-struct EspnApiMembersResponse {
+struct EspnApiTeamsResponse {
     members: Vec<EspnMember>,
+    teams: Vec<EspnTeam>,
+    settings: Option<EspnSettings>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnSettings {
+    // number of teams
+    size: Option<u32>,
+    roster_settings: EspnRosterSettings,
+    acquisition_settings: Option<EspnAcquisitionSettings>,
+    schedule_settings: Option<EspnScheduleSettings>,
+    scoring_settings: Option<EspnScoringSettings>,
+    trade_settings: Option<EspnTradeSettings>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnScoringSettings {
+    #[serde(default)]
+    scoring_items: Vec<EspnScoringItem>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnScoringItem {
+    stat_id: u32,
+    points: f64,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnTradeSettings {
+    // milliseconds since the epoch, or missing if there isn't one
+    deadline_date: Option<i64>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnScheduleSettings {
+    // regular season matchups
+    matchup_period_count: u32,
+    playoff_team_count: u32,
+    // e.g. TOTAL_POINTS_SCORED or H2H_RECORD
+    playoff_seeding_rule: Option<String>,
+    // matchup period ID -> the scoring periods (NFL weeks) it's made of, which is more than one
+    // for playoff rounds that last a couple of weeks
+    #[serde(default)]
+    matchup_periods: HashMap<String, Vec<u32>>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnAcquisitionSettings {
+    is_using_acquisition_budget: bool,
+    acquisition_budget: u32,
+    // waiver priority goes back to reverse standings every week instead of rolling
+    #[serde(default)]
+    waiver_order_reset: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnRosterSettings {
+    // slot ID -> how many of that slot each team has
+    lineup_slot_counts: HashMap<String, u32>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnApiMatchupsResponse {
+    members: Vec<EspnMember>,
+    teams: Vec<EspnTeam>,
+    schedule: Vec<EspnScheduleItem>,
+    status: EspnLeagueStatus,
 }
 
 #[derive(Deserialize, Debug)]
@@ -21,11 +148,218 @@ pub struct EspnMember {
     id: String,
 }
 
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnTeam {
+    id: u32,
+    location: Option<String>,
+    nickname: Option<String>,
+    name: Option<String>,
+    logo: Option<String>,
+    #[serde(default)]
+    owners: Vec<String>,
+    record: Option<EspnTeamRecord>,
+    // 0 until the playoffs are seeded
+    playoff_seed: Option<u32>,
+    roster: Option<EspnRoster>,
+    transaction_counter: Option<EspnTransactionCounter>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnTransactionCounter {
+    #[serde(default)]
+    acquisition_budget_spent: u32,
+}
+
+#[derive(Deserialize, Debug)]
+struct EspnTeamRecord {
+    overall: EspnRecord,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnRecord {
+    wins: u32,
+    losses: u32,
+    ties: u32,
+    points_for: f64,
+    points_against: f64,
+}
+
+#[derive(Deserialize, Debug)]
+struct EspnRoster {
+    entries: Vec<EspnRosterEntry>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnRosterEntry {
+    player_id: i64,
+    lineup_slot_id: u32,
+    player_pool_entry: EspnPlayerPoolEntry,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnPlayerPoolEntry {
+    player: EspnPlayer,
+    applied_stat_total: Option<f64>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnPlayer {
+    full_name: String,
+    default_position_id: u32,
+    pro_team_id: u32,
+    injury_status: Option<String>,
+    #[serde(default)]
+    stats: Vec<EspnPlayerStats>,
+}
+
+#[derive(Deserialize, Debug)]
+struct EspnPlayerInfoResponse {
+    players: Vec<EspnPoolPlayer>,
+}
+
+#[derive(Deserialize, Debug)]
+struct EspnPoolPlayer {
+    id: i64,
+    player: EspnPlayer,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnPlayerStats {
+    scoring_period_id: u32,
+    stat_source_id: u32,
+    applied_total: Option<f64>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnScheduleItem {
+    id: u32,
+    matchup_period_id: u32,
+    home: EspnMatchupTeam,
+    away: Option<EspnMatchupTeam>,
+    // HOME, AWAY, TIE, or UNDECIDED until the game is final
+    winner: Option<String>,
+    // NONE in the regular season, WINNERS_BRACKET for real playoff games, and
+    // WINNERS_CONSOLATION_LADDER or LOSERS_CONSOLATION_LADDER for the rest
+    playoff_tier_type: Option<String>,
+}
+
+impl EspnMatchupTeam {
+    // Points over every week of the matchup so far, which for rounds that last a couple of weeks
+    // is more than the week in progress.
+    fn points(&self) -> Option<f64> {
+        if self.points_by_scoring_period.is_empty() {
+            self.total_points
+        } else {
+            Some(self.points_by_scoring_period.values().sum())
+        }
+    }
+}
+
+impl EspnScheduleItem {
+    fn is_final(&self) -> bool {
+        self.winner.as_deref().is_some_and(|w| w != "UNDECIDED")
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct EspnApiStatusResponse {
+    status: EspnHistoryStatus,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnHistoryStatus {
+    #[serde(default)]
+    previous_seasons: Vec<u32>,
+}
+
+#[derive(Deserialize, Debug)]
+struct EspnApiScheduleResponse {
+    members: Vec<EspnMember>,
+    teams: Vec<EspnTeam>,
+    schedule: Vec<EspnScheduleItem>,
+    settings: EspnSettings,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnApiSettingsResponse {
+    season_id: u32,
+    settings: EspnSettings,
+    status: Option<EspnSeasonStatus>,
+    draft_detail: Option<EspnDraftDetail>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnSeasonStatus {
+    // false once the season's over
+    is_active: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnDraftDetail {
+    #[serde(default)]
+    drafted: bool,
+    #[serde(default)]
+    in_progress: bool,
+    // the ones made so far
+    #[serde(default)]
+    picks: Vec<EspnDraftPick>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnDraftPick {
+    overall_pick_number: u32,
+    round_id: u32,
+    team_id: u32,
+    player_id: i64,
+    #[serde(default)]
+    keeper: bool,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnApiDraftResponse {
+    members: Vec<EspnMember>,
+    teams: Vec<EspnTeam>,
+    settings: EspnSettings,
+    draft_detail: Option<EspnDraftDetail>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnMatchupTeam {
+    team_id: u32,
+    total_points: Option<f64>,
+    // scoring period (NFL week) -> points, one entry per week of the matchup played so far
+    #[serde(default)]
+    points_by_scoring_period: HashMap<String, f64>,
+    roster_for_current_scoring_period: Option<EspnRoster>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct EspnLeagueStatus {
+    current_matchup_period: u32,
+}
+
 pub struct EspnClient {
     league_id: u64,
     year: u32,
     espn_s2: String,
     swid: String,
+    schedule_cache: super::ScheduleCache,
 }
 
 impl EspnClient {
@@ -35,10 +369,37 @@ impl EspnClient {
             year,
             espn_s2,
             swid,
+            schedule_cache: super::ScheduleCache::default(),
         }
     }
 
-    async fn send_request(&self, views: Vec<String>) -> serde_json::Value {
+    async fn send_request(
+        &self,
+        views: Vec<String>,
+        scoring_period: Option<u32>,
+    ) -> Result<serde_json::Value, Box<dyn Error>> {
+        self.send_filtered_request(views, scoring_period, None)
+            .await
+    }
+
+    // Some views, like the player pool, need a filter to say which players and stats to send.
+    async fn send_filtered_request(
+        &self,
+        views: Vec<String>,
+        scoring_period: Option<u32>,
+        filter: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, Box<dyn Error>> {
+        self.send_season_request(self.year, views, scoring_period, filter)
+            .await
+    }
+
+    async fn send_season_request(
+        &self,
+        year: u32,
+        views: Vec<String>,
+        scoring_period: Option<u32>,
+        filter: Option<serde_json::Value>,
+    ) -> Result<serde_json::Value, Box<dyn Error>> {
         let mut headers = HeaderMap::new();
         // TODO: this assumes leagues are private, because all of mine are
         headers.insert(
@@ -46,35 +407,373 @@ impl EspnClient {
             HeaderValue::from_str(format!("SWID={}; espn_s2={}", self.swid, self.espn_s2).as_str())
                 .unwrap(),
         );
+        if let Some(f) = filter {
+            headers.insert("x-fantasy-filter", HeaderValue::from_str(&f.to_string())?);
+        }
         let client = reqwest::Client::new();
-        let mut req = client
-            .get(format!(
+        let mut req = if year >= FIRST_CURRENT_SEASON {
+            client.get(format!(
                 "{}/{}/{}/{}",
-                ESPN_API_URL, self.year, LEAGUE_API_PATH, self.league_id
+                ESPN_API_URL, year, LEAGUE_API_PATH, self.league_id
             ))
-            .headers(headers);
+        } else {
+            client
+                .get(format!("{}/{}", ESPN_HISTORY_URL, self.league_id))
+                .query(&[("seasonId", year)])
+        }
+        .headers(headers);
         for view in views {
             req = req.query(&[("view", view)]);
         }
+        if let Some(period) = scoring_period {
+            req = req.query(&[("scoringPeriodId", period)]);
+        }
         println!("sending request:\n{:?}", req);
-        let resp = req
-            .send()
-            .await
-            .unwrap()
-            .json::<serde_json::Value>()
-            .await
-            .unwrap();
-        resp
+        let resp = req.send().await?.json::<serde_json::Value>().await?;
+        match resp {
+            serde_json::Value::Array(mut leagues) if year < FIRST_CURRENT_SEASON => {
+                Ok(leagues.pop().ok_or("no league history for that season")?)
+            }
+            resp => Ok(resp),
+        }
+    }
+
+    async fn fetch_teams(
+        &self,
+        views: Vec<String>,
+    ) -> Result<EspnApiTeamsResponse, Box<dyn Error>> {
+        let resp = self.send_request(views, None).await?;
+        Ok(serde_json::from_value(resp)?)
+    }
+
+    fn team_from_espn(members: &[EspnMember], team: &EspnTeam) -> super::FantasyTeam {
+        let team_name = match (&team.name, &team.location, &team.nickname) {
+            (Some(name), _, _) => name.clone(),
+            (None, Some(location), Some(nickname)) => format!("{} {}", location, nickname),
+            _ => format!("Team {}", team.id),
+        };
+        let owner_name = team
+            .owners
+            .first()
+            .and_then(|owner| members.iter().find(|m| &m.id == owner))
+            .map(|m| m.display_name.clone())
+            .unwrap_or_default();
+        super::FantasyTeam {
+            id: team.id.to_string(),
+            team_name,
+            owner_name,
+            avatar_url: team.logo.clone(),
+        }
+    }
+
+    fn teams_by_id(members: &[EspnMember], teams: &[EspnTeam]) -> HashMap<u32, super::FantasyTeam> {
+        teams
+            .iter()
+            .map(|t| (t.id, EspnClient::team_from_espn(members, t)))
+            .collect()
+    }
+
+    fn matchups_for_period(
+        teams: &HashMap<u32, super::FantasyTeam>,
+        schedule: &[EspnScheduleItem],
+        period: u32,
+    ) -> Vec<super::FantasyMatchup> {
+        let mut matchups: Vec<super::FantasyMatchup> = vec![];
+        for item in schedule.iter() {
+            if item.matchup_period_id != period {
+                continue;
+            }
+            // a missing away team is a bye
+            let away = match &item.away {
+                Some(a) => a,
+                None => continue,
+            };
+            if let (Some(team1), Some(team2)) =
+                (teams.get(&item.home.team_id), teams.get(&away.team_id))
+            {
+                matchups.push(super::FantasyMatchup {
+                    team1: team1.clone(),
+                    team2: team2.clone(),
+                    score1: item.home.points(),
+                    score2: away.points(),
+                    week_num: period,
+                });
+            }
+        }
+        matchups
+    }
+
+    // Starting slots in slot ID order, then the bench and IR.
+    fn roster_positions(settings: &EspnRosterSettings) -> Vec<String> {
+        let mut slots: Vec<(u32, u32)> = settings
+            .lineup_slot_counts
+            .iter()
+            .filter_map(|(slot, count)| slot.parse::<u32>().ok().map(|s| (s, *count)))
+            .collect();
+        slots.sort_unstable_by_key(|(slot, _)| {
+            (*slot == BENCH_SLOT_ID || *slot == IR_SLOT_ID, *slot)
+        });
+        slots
+            .into_iter()
+            .filter_map(|(slot, count)| {
+                let name = match slot {
+                    BENCH_SLOT_ID => "BN",
+                    IR_SLOT_ID => "IR",
+                    _ => LINEUP_SLOT_MAP.get(&slot)?,
+                };
+                Some(vec![name.to_string(); count as usize])
+            })
+            .flatten()
+            .collect()
+    }
+
+    // A playoff game, with total points that ESPN has already summed over every week of it.
+    // Byes are left out.
+    fn bracket_game(
+        teams: &HashMap<u32, super::FantasyTeam>,
+        seeds: &HashMap<u32, u32>,
+        settings: &EspnScheduleSettings,
+        item: &EspnScheduleItem,
+    ) -> Option<super::BracketGame> {
+        let away = item.away.as_ref()?;
+        let side = |t: &EspnMatchupTeam| super::BracketTeam {
+            team: teams.get(&t.team_id).cloned(),
+            seed: seeds.get(&t.team_id).copied(),
+            from: None,
+            score: t.points(),
+        };
+        let winner = match item.winner.as_deref() {
+            Some("HOME") => teams.get(&item.home.team_id),
+            Some("AWAY") => teams.get(&away.team_id),
+            _ => None,
+        };
+        let period = item.matchup_period_id;
+        Some(super::BracketGame {
+            id: item.id,
+            round: period.saturating_sub(settings.matchup_period_count),
+            week_nums: settings
+                .matchup_periods
+                .get(&period.to_string())
+                .cloned()
+                .unwrap_or_else(|| vec![period]),
+            team1: side(&item.home),
+            team2: side(away),
+            winner: winner.map(|t| t.id.clone()),
+            place: None,
+        })
+    }
+
+    // The playoff games in a season's schedule, one bracket per tier that has any.
+    fn brackets(resp: &EspnApiScheduleResponse) -> Result<Vec<super::Bracket>, Box<dyn Error>> {
+        let settings = resp
+            .settings
+            .schedule_settings
+            .as_ref()
+            .ok_or("league has no schedule settings")?;
+        let teams = EspnClient::teams_by_id(&resp.members, &resp.teams);
+        let seeds: HashMap<u32, u32> = resp
+            .teams
+            .iter()
+            .filter_map(|t| Some((t.id, t.playoff_seed.filter(|s| *s > 0)?)))
+            .collect();
+
+        let mut brackets = vec![];
+        for (tier, kind) in [
+            ("WINNERS_BRACKET", super::BracketKind::Winners),
+            (
+                "WINNERS_CONSOLATION_LADDER",
+                super::BracketKind::Consolation,
+            ),
+            ("LOSERS_CONSOLATION_LADDER", super::BracketKind::Losers),
+        ] {
+            let games: Vec<super::BracketGame> = resp
+                .schedule
+                .iter()
+                .filter(|i| i.playoff_tier_type.as_deref() == Some(tier))
+                .filter_map(|i| EspnClient::bracket_game(&teams, &seeds, settings, i))
+                .collect();
+            if !games.is_empty() {
+                brackets.push(super::Bracket { kind, games });
+            }
+        }
+        Ok(brackets)
+    }
+
+    // Every finished game in a season's schedule, leaving out consolation games.
+    fn season_results(resp: &EspnApiScheduleResponse, season: &str) -> super::SeasonResults {
+        let teams: HashMap<u32, super::PastTeam> = resp
+            .teams
+            .iter()
+            .map(|t| {
+                let team = EspnClient::team_from_espn(&resp.members, t);
+                let owner_id = t.owners.first().cloned().unwrap_or(team.id);
+                (
+                    t.id,
+                    super::PastTeam {
+                        owner_id,
+                        owner_name: team.owner_name,
+                        team_name: team.team_name,
+                    },
+                )
+            })
+            .collect();
+        // the final is the only winners bracket game in the last matchup period
+        let final_period = resp
+            .schedule
+            .iter()
+            .filter(|i| i.playoff_tier_type.as_deref() == Some("WINNERS_BRACKET"))
+            .map(|i| i.matchup_period_id)
+            .max();
+        let mut matchups = vec![];
+        for item in resp.schedule.iter().filter(|i| i.is_final()) {
+            let playoff = match item.playoff_tier_type.as_deref() {
+                None | Some("NONE") => false,
+                Some("WINNERS_BRACKET") => true,
+                // consolation games
+                _ => continue,
+            };
+            let away = match &item.away {
+                Some(a) => a,
+                None => continue,
+            };
+            if let (Some(team1), Some(team2), Some(score1), Some(score2)) = (
+                teams.get(&item.home.team_id),
+                teams.get(&away.team_id),
+                item.home.points(),
+                away.points(),
+            ) {
+                matchups.push(super::PastMatchup {
+                    season: season.to_string(),
+                    week_num: item.matchup_period_id,
+                    team1: team1.clone(),
+                    team2: team2.clone(),
+                    score1,
+                    score2,
+                    playoff,
+                    championship: playoff && Some(item.matchup_period_id) == final_period,
+                });
+            }
+        }
+        super::SeasonResults {
+            matchups,
+            complete: !resp.schedule.is_empty()
+                && resp
+                    .schedule
+                    .iter()
+                    .filter(|i| i.away.is_some())
+                    .all(|i| i.is_final()),
+        }
+    }
+
+    // The most-owned players with their stats for the recent weeks, this one and the season.
+    // ESPN applies the league's scoring to every stat line it sends back, projections included.
+    async fn fetch_player_pool(
+        &self,
+        week_num: u32,
+        statuses: Option<Vec<&str>>,
+    ) -> Result<EspnPlayerInfoResponse, Box<dyn Error>> {
+        let mut filter = serde_json::json!({
+            "players": {
+                "limit": PLAYER_SEARCH_LIMIT,
+                "sortPercOwned": {"sortPriority": 1, "sortAsc": false},
+                "filterStatsForTopScoringPeriodIds": {
+                    "value": super::RECENT_WEEKS + 1,
+                    "additionalValue": [format!("00{}", self.year), format!("10{}", self.year)],
+                },
+            }
+        });
+        if let Some(statuses) = statuses {
+            filter["players"]["filterStatus"] = serde_json::json!({ "value": statuses });
+        }
+        let resp = self
+            .send_filtered_request(
+                vec!["kona_player_info".to_string()],
+                Some(week_num),
+                Some(filter),
+            )
+            .await?;
+        Ok(serde_json::from_value(resp)?)
+    }
+
+    // Specific players, with their season totals.
+    async fn fetch_players_by_id(
+        &self,
+        player_ids: &[i64],
+    ) -> Result<EspnPlayerInfoResponse, Box<dyn Error>> {
+        let filter = serde_json::json!({
+            "players": {
+                "filterIds": {"value": player_ids},
+                "limit": player_ids.len(),
+                "filterStatsForTopScoringPeriodIds": {
+                    "value": 1,
+                    "additionalValue": [format!("00{}", self.year)],
+                },
+            }
+        });
+        let resp = self
+            .send_filtered_request(vec!["kona_player_info".to_string()], None, Some(filter))
+            .await?;
+        Ok(serde_json::from_value(resp)?)
+    }
+
+    fn season_points(p: &EspnPoolPlayer) -> Option<f64> {
+        // season totals are filed under scoring period 0
+        p.player
+            .stats
+            .iter()
+            .find(|s| s.scoring_period_id == 0 && s.stat_source_id == ACTUAL_STAT_SOURCE_ID)
+            .and_then(|s| s.applied_total)
+    }
+
+    fn projection_from_pool(p: &EspnPoolPlayer, week_num: u32) -> super::PlayerProjection {
+        let stat = |source: u32, week: u32| {
+            p.player
+                .stats
+                .iter()
+                .find(|s| s.scoring_period_id == week && s.stat_source_id == source)
+                .and_then(|s| s.applied_total)
+        };
+        let first_recent_week = week_num.saturating_sub(super::RECENT_WEEKS).max(1);
+        super::PlayerProjection {
+            player: EspnClient::player_from_espn(p.id, &p.player),
+            projected: stat(PROJECTED_STAT_SOURCE_ID, week_num),
+            recent: (first_recent_week..week_num)
+                .filter_map(|week| stat(ACTUAL_STAT_SOURCE_ID, week))
+                .collect(),
+            // season totals are filed under scoring period 0
+            rest_of_season: stat(PROJECTED_STAT_SOURCE_ID, 0)
+                .map(|p| (p - stat(ACTUAL_STAT_SOURCE_ID, 0).unwrap_or(0.0)).max(0.0)),
+            season_points: stat(ACTUAL_STAT_SOURCE_ID, 0),
+        }
+    }
+
+    fn player_from_entry(entry: &EspnRosterEntry) -> super::FantasyPlayer {
+        EspnClient::player_from_espn(entry.player_id, &entry.player_pool_entry.player)
+    }
+
+    fn player_from_espn(id: i64, player: &EspnPlayer) -> super::FantasyPlayer {
+        super::FantasyPlayer {
+            id: id.to_string(),
+            name: player.full_name.clone(),
+            position: POSITION_MAP
+                .get(&player.default_position_id)
+                .unwrap_or(&"")
+                .to_string(),
+            nfl_team: PRO_TEAM_MAP.get(&player.pro_team_id).map(|t| t.to_string()),
+            injury_status: player.injury_status.clone(),
+        }
     }
 }
 
 #[async_trait]
 impl super::FantasyClient for EspnClient {
     async fn get_teams(&self) -> Result<Vec<super::FantasyTeam>, Box<dyn Error>> {
-        let teams_resp = self.send_request(vec!["mTeams".to_string()]).await;
-        let resp: EspnApiMembersResponse = serde_json::from_value(teams_resp).unwrap();
-        println!("got resp:\n{:?}", resp);
-        let teams: Vec<super::FantasyTeam> = vec![];
+        let resp = self.fetch_teams(vec!["mTeam".to_string()]).await?;
+        let teams = resp
+            .teams
+            .iter()
+            .map(|t| EspnClient::team_from_espn(&resp.members, t))
+            .collect();
 
         Ok(teams)
     }
@@ -83,7 +782,625 @@ impl super::FantasyClient for EspnClient {
         &self,
         week_num: Option<u32>,
     ) -> Result<Vec<super::FantasyMatchup>, Box<dyn Error>> {
-        let matchups: Vec<super::FantasyMatchup> = vec![];
-        Ok(matchups)
+        let resp = self
+            .send_request(vec!["mTeam".to_string(), "mMatchupScore".to_string()], None)
+            .await?;
+        let resp: EspnApiMatchupsResponse = serde_json::from_value(resp)?;
+        let req_week_num = week_num.unwrap_or(resp.status.current_matchup_period);
+
+        let teams = EspnClient::teams_by_id(&resp.members, &resp.teams);
+        Ok(EspnClient::matchups_for_period(
+            &teams,
+            &resp.schedule,
+            req_week_num,
+        ))
+    }
+
+    // ESPN sends the whole season's schedule at once, so this is a single request until every
+    // week has been played, and none after that.
+    async fn get_schedule(&self) -> Result<super::Schedule, Box<dyn Error>> {
+        if let Some(schedule) = self.schedule_cache.complete() {
+            return Ok(schedule);
+        }
+        let resp = self
+            .send_request(
+                vec![
+                    "mTeam".to_string(),
+                    "mMatchup".to_string(),
+                    "mSettings".to_string(),
+                ],
+                None,
+            )
+            .await?;
+        let resp: EspnApiScheduleResponse = serde_json::from_value(resp)?;
+        let regular_weeks = resp
+            .settings
+            .schedule_settings
+            .as_ref()
+            .ok_or("league has no schedule settings")?
+            .matchup_period_count;
+        let teams = EspnClient::teams_by_id(&resp.members, &resp.teams);
+        let weeks = (1..=regular_weeks)
+            .map(|week_num| {
+                let items: Vec<&EspnScheduleItem> = resp
+                    .schedule
+                    .iter()
+                    .filter(|i| i.matchup_period_id == week_num && i.away.is_some())
+                    .collect();
+                super::ScheduleWeek {
+                    week_num,
+                    matchups: EspnClient::matchups_for_period(&teams, &resp.schedule, week_num),
+                    played: !items.is_empty() && items.iter().all(|i| i.is_final()),
+                }
+            })
+            .collect();
+        let schedule = super::Schedule { weeks };
+        self.schedule_cache.store(&schedule);
+        Ok(schedule)
+    }
+
+    async fn get_standings(&self) -> Result<Vec<super::FantasyStanding>, Box<dyn Error>> {
+        let resp = self.fetch_teams(vec!["mTeam".to_string()]).await?;
+        let mut standings: Vec<super::FantasyStanding> = vec![];
+        for team in resp.teams.iter() {
+            if let Some(record) = &team.record {
+                standings.push(super::FantasyStanding {
+                    team: EspnClient::team_from_espn(&resp.members, team),
+                    wins: record.overall.wins,
+                    losses: record.overall.losses,
+                    ties: record.overall.ties,
+                    points_for: record.overall.points_for,
+                    points_against: record.overall.points_against,
+                });
+            }
+        }
+        super::sort_standings(&mut standings);
+        Ok(standings)
+    }
+
+    async fn get_rosters(&self) -> Result<Vec<super::FantasyRoster>, Box<dyn Error>> {
+        let resp = self
+            .fetch_teams(vec![
+                "mTeam".to_string(),
+                "mRoster".to_string(),
+                "mSettings".to_string(),
+            ])
+            .await?;
+        // ESPN leaves empty slots out of the roster entirely, so count them up from the settings
+        let mut starting_slots: Vec<(u32, u32)> = resp
+            .settings
+            .iter()
+            .flat_map(|s| s.roster_settings.lineup_slot_counts.iter())
+            .filter_map(|(slot, count)| slot.parse::<u32>().ok().map(|s| (s, *count)))
+            .filter(|(slot, count)| *count > 0 && LINEUP_SLOT_MAP.contains_key(slot))
+            .collect();
+        starting_slots.sort_unstable();
+        let mut rosters: Vec<super::FantasyRoster> = vec![];
+        for team in resp.teams.iter() {
+            let entries = match &team.roster {
+                Some(r) => &r.entries,
+                None => continue,
+            };
+            let (bench, starters): (Vec<&EspnRosterEntry>, Vec<&EspnRosterEntry>) = entries
+                .iter()
+                .partition(|e| e.lineup_slot_id == BENCH_SLOT_ID || e.lineup_slot_id == IR_SLOT_ID);
+            let (reserve, bench): (Vec<&EspnRosterEntry>, Vec<&EspnRosterEntry>) = bench
+                .into_iter()
+                .partition(|e| e.lineup_slot_id == IR_SLOT_ID);
+            let mut starting: Vec<super::FantasyPlayer> = vec![];
+            for (slot, count) in starting_slots.iter() {
+                let filled: Vec<&&EspnRosterEntry> = starters
+                    .iter()
+                    .filter(|e| e.lineup_slot_id == *slot)
+                    .collect();
+                starting.extend(filled.iter().map(|e| EspnClient::player_from_entry(e)));
+                for _ in filled.len()..*count as usize {
+                    starting.push(super::FantasyPlayer::empty_slot(LINEUP_SLOT_MAP[slot]));
+                }
+            }
+            // anything in a slot we don't know about still counts as starting
+            starting.extend(
+                starters
+                    .iter()
+                    .filter(|e| !starting_slots.iter().any(|(s, _)| *s == e.lineup_slot_id))
+                    .map(|e| EspnClient::player_from_entry(e)),
+            );
+            rosters.push(super::FantasyRoster {
+                team: EspnClient::team_from_espn(&resp.members, team),
+                starters: starting,
+                bench: bench
+                    .into_iter()
+                    .map(EspnClient::player_from_entry)
+                    .collect(),
+                reserve: reserve
+                    .into_iter()
+                    .map(EspnClient::player_from_entry)
+                    .collect(),
+                taxi: vec![],
+            });
+        }
+        Ok(rosters)
+    }
+
+    async fn get_lineups(
+        &self,
+        week_num: u32,
+    ) -> Result<Vec<super::FantasyLineup>, Box<dyn Error>> {
+        let resp = self
+            .send_request(
+                vec!["mTeam".to_string(), "mBoxscore".to_string()],
+                Some(week_num),
+            )
+            .await?;
+        let resp: EspnApiMatchupsResponse = serde_json::from_value(resp)?;
+
+        let mut lineups: Vec<super::FantasyLineup> = vec![];
+        for item in resp.schedule.iter() {
+            if item.matchup_period_id != week_num {
+                continue;
+            }
+            for side in std::iter::once(&item.home).chain(item.away.iter()) {
+                let team = match resp.teams.iter().find(|t| t.id == side.team_id) {
+                    Some(t) => EspnClient::team_from_espn(&resp.members, t),
+                    None => continue,
+                };
+                let entries = match &side.roster_for_current_scoring_period {
+                    Some(r) => &r.entries,
+                    None => continue,
+                };
+                let score = |e: &&EspnRosterEntry| super::PlayerScore {
+                    player: EspnClient::player_from_entry(e),
+                    points: e.player_pool_entry.applied_stat_total.unwrap_or(0.0),
+                    projected: Some(
+                        e.player_pool_entry
+                            .player
+                            .stats
+                            .iter()
+                            .find(|s| {
+                                s.scoring_period_id == week_num
+                                    && s.stat_source_id == PROJECTED_STAT_SOURCE_ID
+                            })
+                            .and_then(|s| s.applied_total)
+                            .unwrap_or(0.0),
+                    ),
+                };
+                let (bench, starters): (Vec<&EspnRosterEntry>, Vec<&EspnRosterEntry>) =
+                    entries.iter().partition(|e| {
+                        e.lineup_slot_id == BENCH_SLOT_ID || e.lineup_slot_id == IR_SLOT_ID
+                    });
+                lineups.push(super::FantasyLineup {
+                    team,
+                    starters: starters.iter().map(score).collect(),
+                    bench: bench.iter().map(score).collect(),
+                });
+            }
+        }
+        Ok(lineups)
+    }
+
+    async fn search_players(
+        &self,
+        names: &[String],
+        week_num: u32,
+    ) -> Result<Vec<Option<super::PlayerProjection>>, Box<dyn Error>> {
+        let pool = self.fetch_player_pool(week_num, None).await?;
+        let found = names
+            .iter()
+            .map(|name| {
+                // the pool is already sorted by ownership, which makes a good tie-breaker
+                let p = players::best_match(
+                    name,
+                    pool.players
+                        .iter()
+                        .enumerate()
+                        .map(|(i, p)| (p.player.full_name.as_str(), i as u64, p)),
+                )?;
+                Some(EspnClient::projection_from_pool(p, week_num))
+            })
+            .collect();
+        Ok(found)
+    }
+
+    async fn get_free_agents(
+        &self,
+        week_num: u32,
+    ) -> Result<Vec<super::PlayerProjection>, Box<dyn Error>> {
+        let pool = self
+            .fetch_player_pool(week_num, Some(vec!["FREEAGENT", "WAIVERS"]))
+            .await?;
+        Ok(pool
+            .players
+            .iter()
+            .map(|p| EspnClient::projection_from_pool(p, week_num))
+            .collect())
+    }
+
+    async fn get_faab_balances(&self) -> Result<Vec<super::FaabBalance>, Box<dyn Error>> {
+        let resp = self
+            .fetch_teams(vec!["mTeam".to_string(), "mSettings".to_string()])
+            .await?;
+        let budget = match resp
+            .settings
+            .as_ref()
+            .and_then(|s| s.acquisition_settings.as_ref())
+        {
+            Some(a) if a.is_using_acquisition_budget => a.acquisition_budget,
+            _ => return Ok(vec![]),
+        };
+        Ok(resp
+            .teams
+            .iter()
+            .map(|t| super::FaabBalance {
+                team: EspnClient::team_from_espn(&resp.members, t),
+                remaining: budget.saturating_sub(
+                    t.transaction_counter
+                        .as_ref()
+                        .map_or(0, |c| c.acquisition_budget_spent),
+                ),
+                budget,
+            })
+            .collect())
+    }
+
+    async fn get_playoff_format(&self) -> Result<super::PlayoffFormat, Box<dyn Error>> {
+        let resp = self
+            .fetch_teams(vec!["mTeam".to_string(), "mSettings".to_string()])
+            .await?;
+        let schedule = resp
+            .settings
+            .and_then(|s| s.schedule_settings)
+            .ok_or("league has no schedule settings")?;
+        let tiebreaker = match schedule.playoff_seeding_rule.as_deref() {
+            Some("H2H_RECORD") => super::Tiebreaker::HeadToHead,
+            _ => super::Tiebreaker::PointsFor,
+        };
+        Ok(super::PlayoffFormat::new(
+            schedule.playoff_team_count,
+            tiebreaker,
+        ))
+    }
+
+    async fn get_league_settings(&self) -> Result<super::LeagueSettings, Box<dyn Error>> {
+        let resp = self
+            .send_request(vec!["mSettings".to_string()], None)
+            .await?;
+        let resp: EspnApiSettingsResponse = serde_json::from_value(resp)?;
+        let settings = resp.settings;
+        let schedule = settings
+            .schedule_settings
+            .ok_or("league has no schedule settings")?;
+        let period_weeks = |period: u32| {
+            schedule
+                .matchup_periods
+                .get(&period.to_string())
+                .cloned()
+                .unwrap_or_else(|| vec![period])
+        };
+        let first_playoff_period = schedule.matchup_period_count + 1;
+        let playoff_round_weeks: Vec<Vec<u32>> =
+            (0..super::playoff_rounds(schedule.playoff_team_count))
+                .map(|round| period_weeks(first_playoff_period + round))
+                .collect();
+
+        let status = match (&resp.draft_detail, &resp.status) {
+            (Some(d), _) if d.in_progress => super::LeagueStatus::Drafting,
+            (Some(d), _) if !d.drafted => super::LeagueStatus::PreDraft,
+            (_, Some(s)) if s.is_active == Some(false) => super::LeagueStatus::Complete,
+            _ => super::LeagueStatus::InSeason,
+        };
+        let acquisition = settings.acquisition_settings.as_ref();
+        let waiver_type = match acquisition {
+            Some(a) if a.is_using_acquisition_budget => super::WaiverType::Faab,
+            Some(a) if a.waiver_order_reset => super::WaiverType::ReverseStandings,
+            _ => super::WaiverType::Rolling,
+        };
+        // stats we don't have a name for keep ESPN's ID
+        let scoring = settings
+            .scoring_settings
+            .iter()
+            .flat_map(|s| s.scoring_items.iter())
+            .map(|item| {
+                let stat = SCORING_STAT_MAP
+                    .get(&item.stat_id)
+                    .map_or_else(|| format!("espn_{}", item.stat_id), |s| s.to_string());
+                (stat, item.points)
+            })
+            .collect();
+        Ok(super::LeagueSettings {
+            season: resp.season_id.to_string(),
+            status,
+            teams: settings.size.unwrap_or_default(),
+            roster_positions: EspnClient::roster_positions(&settings.roster_settings),
+            scoring,
+            playoff_teams: schedule.playoff_team_count,
+            playoff_week_start: playoff_round_weeks
+                .first()
+                .and_then(|weeks| weeks.first().copied())
+                .unwrap_or(first_playoff_period),
+            playoff_round_weeks,
+            faab_budget: acquisition
+                .filter(|_| waiver_type == super::WaiverType::Faab)
+                .map(|a| a.acquisition_budget),
+            waiver_type,
+            trade_deadline: settings
+                .trade_settings
+                .and_then(|t| t.deadline_date)
+                .filter(|d| *d > 0)
+                .and_then(DateTime::from_timestamp_millis)
+                .map(super::TradeDeadline::Date),
+            previous_league_id: None,
+        })
+    }
+
+    async fn get_draft(&self) -> Result<Option<super::Draft>, Box<dyn Error>> {
+        let resp = self
+            .send_request(
+                vec![
+                    "mTeam".to_string(),
+                    "mSettings".to_string(),
+                    "mDraftDetail".to_string(),
+                ],
+                None,
+            )
+            .await?;
+        let resp: EspnApiDraftResponse = serde_json::from_value(resp)?;
+        let detail = match resp.draft_detail {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        let status = if detail.in_progress {
+            super::DraftStatus::Drafting
+        } else if detail.drafted {
+            super::DraftStatus::Complete
+        } else {
+            super::DraftStatus::Scheduled
+        };
+        // one round per roster spot, not counting IR
+        let rounds = resp
+            .settings
+            .roster_settings
+            .lineup_slot_counts
+            .iter()
+            .filter(|(slot, _)| slot.parse::<u32>().ok() != Some(IR_SLOT_ID))
+            .map(|(_, count)| count)
+            .sum();
+
+        // picks only have player IDs, so look the players up
+        let player_ids: Vec<i64> = detail
+            .picks
+            .iter()
+            .map(|p| p.player_id)
+            .filter(|id| *id > 0)
+            .collect();
+        let players: HashMap<i64, super::FantasyPlayer> = if player_ids.is_empty() {
+            HashMap::new()
+        } else {
+            self.fetch_players_by_id(&player_ids)
+                .await?
+                .players
+                .iter()
+                .map(|p| (p.id, EspnClient::player_from_espn(p.id, &p.player)))
+                .collect()
+        };
+        let teams = EspnClient::teams_by_id(&resp.members, &resp.teams);
+        let mut picks: Vec<super::DraftPick> = detail
+            .picks
+            .iter()
+            .filter(|p| p.player_id > 0)
+            .filter_map(|p| {
+                Some(super::DraftPick {
+                    pick_no: p.overall_pick_number,
+                    round: p.round_id,
+                    team: teams.get(&p.team_id)?.clone(),
+                    player: players.get(&p.player_id)?.clone(),
+                    keeper: p.keeper,
+                })
+            })
+            .collect();
+        picks.sort_by_key(|p| p.pick_no);
+        Ok(Some(super::Draft {
+            id: format!("{}-{}", self.league_id, self.year),
+            status,
+            rounds,
+            picks,
+        }))
+    }
+
+    // ESPN leagues can't trade picks.
+    async fn get_traded_picks(&self) -> Result<Vec<super::TradedPick>, Box<dyn Error>> {
+        Ok(vec![])
+    }
+
+    async fn get_season_points(
+        &self,
+        player_ids: &[String],
+    ) -> Result<HashMap<String, f64>, Box<dyn Error>> {
+        let ids: Vec<i64> = player_ids.iter().filter_map(|id| id.parse().ok()).collect();
+        if ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+        let resp = self.fetch_players_by_id(&ids).await?;
+        Ok(resp
+            .players
+            .iter()
+            .filter_map(|p| Some((p.id.to_string(), EspnClient::season_points(p)?)))
+            .collect())
+    }
+
+    // ESPN only schedules each playoff round once its teams are known, so there's nothing to
+    // say where later rounds' teams come from.
+    async fn get_brackets(&self) -> Result<Vec<super::Bracket>, Box<dyn Error>> {
+        let resp = self
+            .send_request(
+                vec![
+                    "mTeam".to_string(),
+                    "mMatchup".to_string(),
+                    "mSettings".to_string(),
+                ],
+                None,
+            )
+            .await?;
+        let resp: EspnApiScheduleResponse = serde_json::from_value(resp)?;
+        EspnClient::brackets(&resp)
+    }
+
+    async fn get_seasons(&self) -> Result<Vec<super::LeagueSeason>, Box<dyn Error>> {
+        let resp = self.send_request(vec![], None).await?;
+        let resp: EspnApiStatusResponse = serde_json::from_value(resp)?;
+        let mut years = resp.status.previous_seasons;
+        years.push(self.year);
+        years.sort_unstable_by(|a, b| b.cmp(a));
+        Ok(years
+            .into_iter()
+            .map(|y| super::LeagueSeason {
+                season: y.to_string(),
+                league_id: self.league_id.to_string(),
+            })
+            .collect())
+    }
+
+    async fn get_season_results(
+        &self,
+        season: &super::LeagueSeason,
+    ) -> Result<super::SeasonResults, Box<dyn Error>> {
+        let year: u32 = season.season.parse()?;
+        let resp = self
+            .send_season_request(
+                year,
+                vec![
+                    "mTeam".to_string(),
+                    "mMatchup".to_string(),
+                    "mSettings".to_string(),
+                ],
+                None,
+                None,
+            )
+            .await?;
+        let resp: EspnApiScheduleResponse = serde_json::from_value(resp)?;
+        Ok(EspnClient::season_results(&resp, &season.season))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::{BracketKind, PastMatchup};
+
+    // ESPN's totalPoints is the whole matchup period's, so over every week of a two-week round
+    fn side(team_id: u32, points: &[(u32, f64)]) -> serde_json::Value {
+        let by_period: HashMap<String, f64> =
+            points.iter().map(|(w, p)| (w.to_string(), *p)).collect();
+        serde_json::json!({
+            "teamId": team_id,
+            "totalPoints": points.iter().map(|(_, p)| p).sum::<f64>(),
+            "pointsByScoringPeriod": by_period,
+        })
+    }
+
+    // trimmed from an mTeam/mMatchup/mSettings response for a 4-team league whose final lasts
+    // weeks 16 and 17
+    fn schedule_response() -> EspnApiScheduleResponse {
+        let game = |id: u32, period: u32, tier: &str, home, away, winner: &str| {
+            serde_json::json!({
+                "id": id,
+                "matchupPeriodId": period,
+                "playoffTierType": tier,
+                "home": home,
+                "away": away,
+                "winner": winner,
+            })
+        };
+        let team = |id: u32, seed: u32| {
+            serde_json::json!({
+                "id": id,
+                "location": "Team",
+                "nickname": id.to_string(),
+                "owners": [format!("{{OWNER-{}}}", id)],
+                "playoffSeed": seed,
+            })
+        };
+        serde_json::from_value(serde_json::json!({
+            "members": (1..=4)
+                .map(|id| serde_json::json!({
+                    "id": format!("{{OWNER-{}}}", id),
+                    "displayName": format!("owner{}", id),
+                }))
+                .collect::<Vec<_>>(),
+            "teams": [team(1, 1), team(2, 2), team(3, 3), team(4, 4)],
+            "schedule": [
+                game(56, 14, "NONE", side(1, &[(14, 101.0)]), side(2, &[(14, 99.0)]), "HOME"),
+                game(57, 15, "WINNERS_BRACKET", side(1, &[(15, 120.0)]), side(4, &[(15, 80.0)]), "HOME"),
+                game(58, 15, "WINNERS_BRACKET", side(2, &[(15, 90.0)]), side(3, &[(15, 110.5)]), "AWAY"),
+                game(
+                    59,
+                    16,
+                    "WINNERS_BRACKET",
+                    side(1, &[(16, 100.0), (17, 95.0)]),
+                    side(3, &[(16, 110.0), (17, 90.5)]),
+                    "AWAY",
+                ),
+                game(60, 16, "WINNERS_CONSOLATION_LADDER", side(2, &[(16, 70.0), (17, 75.0)]), side(4, &[(16, 60.0)]), "UNDECIDED"),
+            ],
+            "settings": {
+                "rosterSettings": {"lineupSlotCounts": {}},
+                "scheduleSettings": {
+                    "matchupPeriodCount": 14,
+                    "playoffTeamCount": 4,
+                    "matchupPeriods": {"14": [14], "15": [15], "16": [16, 17]},
+                },
+            },
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn brackets_follow_playoff_tiers() {
+        let brackets = EspnClient::brackets(&schedule_response()).unwrap();
+        // regular season games aren't in any bracket, and tiers without games are left out
+        assert_eq!(brackets.len(), 2);
+        assert!(matches!(brackets[0].kind, BracketKind::Winners));
+        assert!(matches!(brackets[1].kind, BracketKind::Consolation));
+
+        let winners = &brackets[0];
+        assert_eq!(winners.rounds(), 2);
+        let semi = winners.games.iter().find(|g| g.id == 58).unwrap();
+        assert_eq!(semi.round, 1);
+        assert_eq!(semi.week_nums, vec![15]);
+        assert_eq!(semi.team1.seed, Some(2));
+        assert_eq!(semi.winner.as_deref(), Some("3"));
+
+        // the final is scored over both of its weeks
+        let last = winners.games.iter().find(|g| g.id == 59).unwrap();
+        assert_eq!(last.round, 2);
+        assert_eq!(last.week_nums, vec![16, 17]);
+        assert_eq!(last.team1.score, Some(195.0));
+        assert_eq!(last.team2.score, Some(200.5));
+        assert_eq!(last.team2.team.as_ref().unwrap().team_name, "Team 3");
+        assert_eq!(last.winner.as_deref(), Some("3"));
+
+        // partway through a two-week round
+        let third = &brackets[1].games[0];
+        assert_eq!(third.team1.score, Some(145.0));
+        assert_eq!(third.team2.score, Some(60.0));
+        assert_eq!(third.winner, None);
+    }
+
+    #[test]
+    fn season_results_score_rounds_like_the_bracket() {
+        let resp = schedule_response();
+        let results = EspnClient::season_results(&resp, "2021");
+        // the consolation game is left out, and isn't over anyway
+        assert_eq!(results.matchups.len(), 4);
+        assert!(!results.complete);
+        assert!(!results.matchups[0].playoff);
+
+        let brackets = EspnClient::brackets(&resp).unwrap();
+        let last = brackets[0].games.iter().find(|g| g.id == 59).unwrap();
+        let championship: Vec<&PastMatchup> =
+            results.matchups.iter().filter(|m| m.championship).collect();
+        assert_eq!(championship.len(), 1);
+        assert_eq!(championship[0].team2.owner_id, "{OWNER-3}");
+        assert_eq!(Some(championship[0].score1), last.team1.score);
+        assert_eq!(Some(championship[0].score2), last.team2.score);
     }
 }
@@ -1,7 +1,10 @@
 use async_trait::async_trait;
-use serde::Deserialize;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::error::Error;
 use std::option::Option;
+use std::sync::RwLock;
 
 pub mod espn;
 pub mod sleeper;
@@ -16,19 +19,515 @@ pub enum FflClientType {
     SLEEPER(sleeper::SleeperClient),
 }
 
+impl FflClient {
+    pub fn client(&self) -> &dyn FantasyClient {
+        match &self.client_type {
+            FflClientType::ESPN(c) => c,
+            FflClientType::SLEEPER(c) => c,
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct FantasyTeam {
-    id: String,
-    team_name: String,
-    owner_name: String,
+    pub id: String,
+    pub team_name: String,
+    pub owner_name: String,
+    pub avatar_url: Option<String>,
+}
+
+#[cfg(test)]
+impl FantasyTeam {
+    // A team for tests, with everything made up from its ID.
+    pub fn test(id: &str) -> FantasyTeam {
+        FantasyTeam {
+            id: id.to_string(),
+            team_name: format!("Team {}", id),
+            owner_name: format!("Owner {}", id),
+            avatar_url: Some(format!("https://example.com/{}.png", id)),
+        }
+    }
 }
 
+#[derive(Clone)]
 pub struct FantasyMatchup {
-    team1: FantasyTeam,
-    team2: FantasyTeam,
-    score1: Option<f64>,
-    score2: Option<f64>,
-    week_num: u32,
+    pub team1: FantasyTeam,
+    pub team2: FantasyTeam,
+    pub score1: Option<f64>,
+    pub score2: Option<f64>,
+    pub week_num: u32,
+}
+
+#[cfg(test)]
+impl FantasyMatchup {
+    // A game between two test teams, with no scores until it's been played.
+    pub fn test(
+        week_num: u32,
+        team1: &str,
+        team2: &str,
+        scores: Option<(f64, f64)>,
+    ) -> FantasyMatchup {
+        FantasyMatchup {
+            team1: FantasyTeam::test(team1),
+            team2: FantasyTeam::test(team2),
+            score1: scores.map(|s| s.0),
+            score2: scores.map(|s| s.1),
+            week_num,
+        }
+    }
+}
+
+// One week of a league's regular season.
+#[derive(Clone)]
+pub struct ScheduleWeek {
+    pub week_num: u32,
+    pub matchups: Vec<FantasyMatchup>,
+    // every game that week is final
+    pub played: bool,
+}
+
+// A league's whole regular season, first week first.
+#[derive(Clone)]
+pub struct Schedule {
+    pub weeks: Vec<ScheduleWeek>,
+}
+
+impl Schedule {
+    pub fn played_matchups(&self) -> Vec<FantasyMatchup> {
+        self.matchups(true)
+    }
+
+    pub fn remaining_matchups(&self) -> Vec<FantasyMatchup> {
+        self.matchups(false)
+    }
+
+    fn matchups(&self, played: bool) -> Vec<FantasyMatchup> {
+        self.weeks
+            .iter()
+            .filter(|w| w.played == played)
+            .flat_map(|w| w.matchups.iter().cloned())
+            .collect()
+    }
+}
+
+// Weeks that have been played won't change, so clients hang on to them instead of asking for
+// them again.
+#[derive(Default)]
+pub struct ScheduleCache {
+    inner: RwLock<CachedSchedule>,
+}
+
+#[derive(Default)]
+struct CachedSchedule {
+    regular_weeks: Option<u32>,
+    played: HashMap<u32, ScheduleWeek>,
+}
+
+impl ScheduleCache {
+    pub fn week(&self, week_num: u32) -> Option<ScheduleWeek> {
+        self.inner.read().unwrap().played.get(&week_num).cloned()
+    }
+
+    // The whole schedule, once every week of it has been played.
+    pub fn complete(&self) -> Option<Schedule> {
+        let cache = self.inner.read().unwrap();
+        let weeks = (1..=cache.regular_weeks?)
+            .map(|w| cache.played.get(&w).cloned())
+            .collect::<Option<Vec<ScheduleWeek>>>()?;
+        Some(Schedule { weeks })
+    }
+
+    pub fn store(&self, schedule: &Schedule) {
+        let mut cache = self.inner.write().unwrap();
+        cache.regular_weeks = Some(schedule.weeks.len() as u32);
+        for week in schedule.weeks.iter().filter(|w| w.played) {
+            cache.played.insert(week.week_num, week.clone());
+        }
+    }
+}
+
+// One season of a league.  Sleeper starts a new league every year, so `league_id` is that
+// season's; ESPN keeps the same one.
+#[derive(Clone, Debug)]
+pub struct LeagueSeason {
+    pub season: String,
+    pub league_id: String,
+}
+
+// A team as it was in some season, identified by its owner since team names change every year.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct PastTeam {
+    pub owner_id: String,
+    pub owner_name: String,
+    pub team_name: String,
+}
+
+#[cfg(test)]
+impl PastTeam {
+    // An owner's team for tests, with their ID made up from their name.
+    pub fn test(owner: &str, team_name: &str) -> PastTeam {
+        PastTeam {
+            owner_id: owner.to_lowercase(),
+            owner_name: owner.to_string(),
+            team_name: team_name.to_string(),
+        }
+    }
+}
+
+// A finished game from any season.  Consolation games are left out.
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct PastMatchup {
+    pub season: String,
+    pub week_num: u32,
+    pub team1: PastTeam,
+    pub team2: PastTeam,
+    pub score1: f64,
+    pub score2: f64,
+    pub playoff: bool,
+    // the final of the winners bracket, which decides the league champion
+    #[serde(default)]
+    pub championship: bool,
+}
+
+#[cfg(test)]
+impl PastMatchup {
+    // A regular season game for tests.
+    pub fn test(
+        season: &str,
+        week_num: u32,
+        team1: &PastTeam,
+        team2: &PastTeam,
+        score1: f64,
+        score2: f64,
+    ) -> PastMatchup {
+        PastMatchup {
+            season: season.to_string(),
+            week_num,
+            team1: team1.clone(),
+            team2: team2.clone(),
+            score1,
+            score2,
+            playoff: false,
+            championship: false,
+        }
+    }
+}
+
+pub struct SeasonResults {
+    pub matchups: Vec<PastMatchup>,
+    // every game of the season has been played, so the results won't change
+    pub complete: bool,
+}
+
+#[derive(Clone)]
+pub struct FantasyStanding {
+    pub team: FantasyTeam,
+    pub wins: u32,
+    pub losses: u32,
+    pub ties: u32,
+    pub points_for: f64,
+    pub points_against: f64,
+}
+
+#[derive(Clone)]
+pub struct FantasyPlayer {
+    pub id: String,
+    pub name: String,
+    pub position: String,
+    pub nfl_team: Option<String>,
+    pub injury_status: Option<String>,
+}
+
+// Sleeper's id for an empty starting slot; ESPN slots are filled in with the same thing
+pub const EMPTY_SLOT_ID: &str = "0";
+
+impl FantasyPlayer {
+    pub fn empty_slot(position: &str) -> FantasyPlayer {
+        FantasyPlayer {
+            id: EMPTY_SLOT_ID.to_string(),
+            name: "(empty)".to_string(),
+            position: position.to_string(),
+            nfl_team: None,
+            injury_status: None,
+        }
+    }
+
+    pub fn is_empty_slot(&self) -> bool {
+        self.id == EMPTY_SLOT_ID
+    }
+}
+
+pub struct FantasyRoster {
+    pub team: FantasyTeam,
+    pub starters: Vec<FantasyPlayer>,
+    pub bench: Vec<FantasyPlayer>,
+    // IR
+    pub reserve: Vec<FantasyPlayer>,
+    // only in dynasty leagues on Sleeper
+    pub taxi: Vec<FantasyPlayer>,
+}
+
+impl FantasyRoster {
+    // Everybody on the team, wherever they are on the roster.  Empty starting slots included.
+    pub fn players(&self) -> impl Iterator<Item = &FantasyPlayer> {
+        self.starters
+            .iter()
+            .chain(self.bench.iter())
+            .chain(self.reserve.iter())
+            .chain(self.taxi.iter())
+    }
+}
+
+#[derive(Clone)]
+pub struct PlayerScore {
+    pub player: FantasyPlayer,
+    pub points: f64,
+    pub projected: Option<f64>,
+}
+
+// How many past weeks of scoring to look at for a player's recent form.
+pub const RECENT_WEEKS: u32 = 3;
+
+// A player's projection for a week under a league's scoring, along with what they scored in
+// the last few weeks they played, oldest first.
+pub struct PlayerProjection {
+    pub player: FantasyPlayer,
+    pub projected: Option<f64>,
+    pub recent: Vec<f64>,
+    // projected points for the rest of the season, this week included
+    pub rest_of_season: Option<f64>,
+    // actual points so far this season
+    pub season_points: Option<f64>,
+}
+
+impl PlayerProjection {
+    pub fn recent_average(&self) -> Option<f64> {
+        if self.recent.is_empty() {
+            None
+        } else {
+            Some(self.recent.iter().sum::<f64>() / self.recent.len() as f64)
+        }
+    }
+}
+
+// Free agent budget left for a team, in leagues that use one.
+pub struct FaabBalance {
+    pub team: FantasyTeam,
+    pub remaining: u32,
+    pub budget: u32,
+}
+
+// How a league breaks ties in the standings when seeding its playoffs.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tiebreaker {
+    PointsFor,
+    HeadToHead,
+}
+
+// How many teams make a league's playoffs and how they're seeded.
+#[derive(Clone, Debug)]
+pub struct PlayoffFormat {
+    pub teams: u32,
+    // the top seeds sit out the first round
+    pub byes: u32,
+    pub tiebreaker: Tiebreaker,
+}
+
+impl PlayoffFormat {
+    // Brackets are filled out to a power of two with byes, e.g. 6 teams means 2 byes.
+    pub fn new(teams: u32, tiebreaker: Tiebreaker) -> PlayoffFormat {
+        PlayoffFormat {
+            teams,
+            byes: teams.next_power_of_two() - teams,
+            tiebreaker,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LeagueStatus {
+    PreDraft,
+    Drafting,
+    InSeason,
+    Complete,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WaiverType {
+    // claims go in priority order, and whoever gets a player goes to the back
+    Rolling,
+    // priority resets to worst team first every week
+    ReverseStandings,
+    Faab,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum TradeDeadline {
+    // trades close after this week
+    Week(u32),
+    Date(DateTime<Utc>),
+}
+
+// A league's rules, for features that need to play by them.
+#[derive(Clone, Debug)]
+pub struct LeagueSettings {
+    pub season: String,
+    pub status: LeagueStatus,
+    pub teams: u32,
+    // one per roster slot, starters first, e.g. QB, RB, RB, FLEX, BN, IR
+    pub roster_positions: Vec<String>,
+    // stat -> points for each one
+    pub scoring: HashMap<String, f64>,
+    pub playoff_teams: u32,
+    pub playoff_week_start: u32,
+    // the weeks each playoff round is played in, first round first
+    pub playoff_round_weeks: Vec<Vec<u32>>,
+    pub waiver_type: WaiverType,
+    // only for FAAB leagues
+    pub faab_budget: Option<u32>,
+    pub trade_deadline: Option<TradeDeadline>,
+    // the same league's previous season, for platforms that start a new league every year
+    pub previous_league_id: Option<String>,
+}
+
+impl LeagueSettings {
+    // The league's last week, the final of its playoffs.
+    pub fn last_week(&self) -> u32 {
+        self.playoff_round_weeks
+            .last()
+            .and_then(|weeks| weeks.last().copied())
+            .unwrap_or(self.playoff_week_start.saturating_sub(1))
+    }
+}
+
+// How many rounds a playoff bracket with this many teams has, counting byes as a round.
+pub fn playoff_rounds(teams: u32) -> u32 {
+    teams.next_power_of_two().trailing_zeros()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DraftStatus {
+    Scheduled,
+    Drafting,
+    Complete,
+}
+
+#[derive(Clone)]
+pub struct DraftPick {
+    // overall, starting at 1
+    pub pick_no: u32,
+    pub round: u32,
+    pub team: FantasyTeam,
+    pub player: FantasyPlayer,
+    pub keeper: bool,
+}
+
+#[derive(Clone)]
+pub struct Draft {
+    pub id: String,
+    pub status: DraftStatus,
+    pub rounds: u32,
+    // the ones made so far, first pick first
+    pub picks: Vec<DraftPick>,
+}
+
+// A draft pick that belongs to somebody other than the team it started with.
+#[derive(Clone)]
+pub struct TradedPick {
+    pub season: String,
+    pub round: u32,
+    pub original_team: FantasyTeam,
+    pub owner: FantasyTeam,
+}
+
+// Where a team in a bracket game comes from, for games whose teams aren't known yet.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BracketSource {
+    WinnerOf(u32),
+    LoserOf(u32),
+}
+
+#[derive(Clone)]
+pub struct BracketTeam {
+    // None until it's decided who plays
+    pub team: Option<FantasyTeam>,
+    pub seed: Option<u32>,
+    pub from: Option<BracketSource>,
+    // summed over every week of the game, once it's started
+    pub score: Option<f64>,
+}
+
+#[derive(Clone)]
+pub struct BracketGame {
+    // what other games' `BracketSource`s refer to
+    pub id: u32,
+    // starting at 1
+    pub round: u32,
+    // the NFL weeks it's scored over, more than one when a round lasts a few weeks
+    pub week_nums: Vec<u32>,
+    pub team1: BracketTeam,
+    pub team2: BracketTeam,
+    // team ID, once the game's over
+    pub winner: Option<String>,
+    // for games that decide a final place, the place their winner finishes in
+    pub place: Option<u32>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BracketKind {
+    Winners,
+    // playing for the places after the winners bracket's
+    Consolation,
+    // playing to stay out of last place
+    Losers,
+}
+
+#[derive(Clone)]
+pub struct Bracket {
+    pub kind: BracketKind,
+    pub games: Vec<BracketGame>,
+}
+
+impl Bracket {
+    pub fn rounds(&self) -> u32 {
+        self.games.iter().map(|g| g.round).max().unwrap_or(0)
+    }
+
+    // Games whose teams are set but that haven't been decided yet.
+    pub fn current_games(&self) -> Vec<&BracketGame> {
+        self.games
+            .iter()
+            .filter(|g| g.winner.is_none() && g.team1.team.is_some() && g.team2.team.is_some())
+            .collect()
+    }
+}
+
+// A team's starters and bench for one week, with the points each player scored.
+pub struct FantasyLineup {
+    pub team: FantasyTeam,
+    pub starters: Vec<PlayerScore>,
+    pub bench: Vec<PlayerScore>,
+}
+
+impl FantasyLineup {
+    pub fn projected_total(&self) -> Option<f64> {
+        self.starters
+            .iter()
+            .map(|p| p.projected)
+            .sum::<Option<f64>>()
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct PowerRankings {
+    pub power: Vec<TeamPower>,
+    pub updated: String,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct TeamPower {
+    pub power: String,
+    pub team: String,
 }
 
 #[derive(Clone, Deserialize, Debug, PartialEq, Eq, Hash)]
@@ -43,14 +542,106 @@ pub struct LeagueConfig {
     pub league_type: LeagueType,
     pub league_id: String,
     pub discord_category_id: String,
+    // the Discord server the league's commands are registered in; without one it gets none
+    pub guild_id: Option<String>,
     pub short_name: String,
+    pub discord_channel_id: Option<String>,
+    pub recap_template: Option<String>,
+    // fantasy team ID -> Discord user ID, for DMing owners
+    #[serde(default)]
+    pub discord_owners: HashMap<String, String>,
+    #[serde(default)]
+    pub lineup_dms: bool,
+    // path to a CSV rank list for the draft board
+    pub draft_rankings: Option<String>,
+    // only for keeper leagues
+    pub keepers: Option<KeeperRules>,
+}
+
+#[derive(Clone, Deserialize, Debug)]
+pub struct KeeperRules {
+    // how many players each team can keep, each costing one of next season's picks
+    pub max_keepers: u32,
+    // how many rounds earlier than where they were drafted a keeper costs, so with 1 a
+    // 5th-rounder costs a 4th and a 1st-rounder can't be kept
+    #[serde(default)]
+    pub round_escalation: u32,
+    // what keeping a player nobody drafted costs, the last round if not set
+    pub undrafted_round: Option<u32>,
 }
 
 #[async_trait]
-trait FantasyClient {
+pub trait FantasyClient: Send + Sync {
     async fn get_teams(&self) -> Result<Vec<FantasyTeam>, Box<dyn Error>>;
     async fn get_matchups(
         &self,
         week_num: Option<u32>,
     ) -> Result<Vec<FantasyMatchup>, Box<dyn Error>>;
+    async fn get_standings(&self) -> Result<Vec<FantasyStanding>, Box<dyn Error>>;
+    // Every regular season week, including ones that haven't been played yet.
+    async fn get_schedule(&self) -> Result<Schedule, Box<dyn Error>>;
+    async fn get_rosters(&self) -> Result<Vec<FantasyRoster>, Box<dyn Error>>;
+    async fn get_lineups(&self, week_num: u32) -> Result<Vec<FantasyLineup>, Box<dyn Error>>;
+    // Looks up players by (fuzzy) name, giving None for any name that doesn't match anyone.
+    async fn search_players(
+        &self,
+        names: &[String],
+        week_num: u32,
+    ) -> Result<Vec<Option<PlayerProjection>>, Box<dyn Error>>;
+    async fn get_free_agents(&self, week_num: u32)
+        -> Result<Vec<PlayerProjection>, Box<dyn Error>>;
+    // Empty if the league doesn't use FAAB.
+    async fn get_faab_balances(&self) -> Result<Vec<FaabBalance>, Box<dyn Error>>;
+    async fn get_playoff_format(&self) -> Result<PlayoffFormat, Box<dyn Error>>;
+    async fn get_league_settings(&self) -> Result<LeagueSettings, Box<dyn Error>>;
+    // This season's draft, or None if one hasn't been set up.
+    async fn get_draft(&self) -> Result<Option<Draft>, Box<dyn Error>>;
+    // Every pick, this season's or a future one, that's been traded away from the team it
+    // started with.  Empty for platforms that don't trade picks.
+    async fn get_traded_picks(&self) -> Result<Vec<TradedPick>, Box<dyn Error>>;
+    // What each player has scored this season under the league's scoring, by player ID.  Players
+    // who haven't scored anything are left out.
+    async fn get_season_points(
+        &self,
+        player_ids: &[String],
+    ) -> Result<HashMap<String, f64>, Box<dyn Error>>;
+    // This season's playoff brackets, winners first.  Empty until the playoffs are set.
+    async fn get_brackets(&self) -> Result<Vec<Bracket>, Box<dyn Error>>;
+    // Every season the league has been around for, newest first, this one included.
+    async fn get_seasons(&self) -> Result<Vec<LeagueSeason>, Box<dyn Error>>;
+    async fn get_season_results(
+        &self,
+        season: &LeagueSeason,
+    ) -> Result<SeasonResults, Box<dyn Error>>;
+}
+
+// Sorts standings by record, then by points scored.
+pub fn sort_standings(standings: &mut [FantasyStanding]) {
+    standings.sort_by(|a, b| {
+        let a_pct =
+            (a.wins as f64 + a.ties as f64 / 2.0) / (a.wins + a.losses + a.ties).max(1) as f64;
+        let b_pct =
+            (b.wins as f64 + b.ties as f64 / 2.0) / (b.wins + b.losses + b.ties).max(1) as f64;
+        b_pct
+            .partial_cmp(&a_pct)
+            .unwrap()
+            .then(b.points_for.partial_cmp(&a.points_for).unwrap())
+    });
+}
+
+// Power rankings are computed offline and published as JSON.  There aren't any for Sleeper yet.
+pub async fn get_power_rankings(
+    url_format: &str,
+    config: &LeagueConfig,
+) -> Result<Option<PowerRankings>, Box<dyn Error>> {
+    let league_type = match config.league_type {
+        LeagueType::SLEEPER => return Ok(None),
+        LeagueType::ESPN => "espn",
+    };
+    let url = url_format
+        .replace("<LEAGUE_ID>", &config.league_id)
+        .replace("<LEAGUE_TYPE>", league_type);
+    println!("fetching power from URL {}", url);
+    let power = reqwest::get(url).await?.json::<PowerRankings>().await?;
+    Ok(Some(power))
 }
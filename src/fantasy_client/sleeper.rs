@@ -1,16 +1,23 @@
+use super::super::players;
 use async_trait::async_trait;
 use log::debug;
 use serde::Deserialize;
 use serde_json;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::fs::{self, File};
 use std::io::{BufWriter, Write};
-use std::sync::RwLock;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 const SLEEPER_API_URL: &str = "https://api.sleeper.app/v1";
+const SLEEPER_AVATAR_URL: &str = "https://sleepercdn.com/avatars/thumbs";
+const SLEEPER_PLAYER_IMAGE_URL: &str = "https://sleepercdn.com/content/nfl/players/thumb";
+const SLEEPER_TEAM_IMAGE_URL: &str = "https://sleepercdn.com/images/team_logos/nfl";
 const PLAYERS_DATA_PATH: &str = "data/sleeper_players.json";
 const SECS_PER_DAY: u64 = 60 * 60 * 24;
+// stats change while games are on, and projections through the week
+const STAT_LINES_TTL: Duration = Duration::from_secs(60 * 60);
 
 #[derive(Deserialize, Debug)]
 struct SleeperTeamMetadata {
@@ -19,8 +26,16 @@ struct SleeperTeamMetadata {
 
 #[derive(Deserialize, Debug)]
 pub struct SleeperNflStateApiResponse {
-    week: u32,
-    season_type: String,
+    pub week: u32,
+    pub season: String,
+    pub season_type: String,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct SleeperTrendingPlayer {
+    pub player_id: String,
+    // how many leagues added or dropped them
+    pub count: u32,
 }
 
 #[derive(Deserialize, Debug)]
@@ -31,10 +46,154 @@ struct SleeperUser {
     avatar: Option<String>,
 }
 
+#[derive(Clone, Deserialize, Debug)]
+struct SleeperRosterSettings {
+    wins: u32,
+    losses: u32,
+    ties: u32,
+    fpts: Option<u32>,
+    fpts_decimal: Option<u32>,
+    fpts_against: Option<u32>,
+    fpts_against_decimal: Option<u32>,
+    waiver_budget_used: Option<u32>,
+}
+
 #[derive(Clone, Deserialize, Debug)]
 struct SleeperRoster {
     roster_id: u32,
     owner_id: String,
+    players: Option<Vec<String>>,
+    starters: Option<Vec<String>>,
+    // both are also in players
+    reserve: Option<Vec<String>>,
+    taxi: Option<Vec<String>>,
+    settings: SleeperRosterSettings,
+}
+
+#[derive(Deserialize, Debug)]
+struct SleeperLeague {
+    season: String,
+    // pre_draft, drafting, in_season or complete
+    status: String,
+    total_rosters: u32,
+    scoring_settings: HashMap<String, f64>,
+    // one per roster slot, starters first, in the same order as a roster's starters
+    #[serde(default)]
+    roster_positions: Vec<String>,
+    settings: SleeperLeagueSettings,
+    // the same league's previous season, if it has one
+    previous_league_id: Option<String>,
+}
+
+// waiver_type 0 is rolling, 1 is reverse standings and 2 is FAAB
+const ROLLING_WAIVER_TYPE: u32 = 0;
+const FAAB_WAIVER_TYPE: u32 = 2;
+// a trade deadline week that never comes
+const NO_TRADE_DEADLINE: u32 = 99;
+
+#[derive(Deserialize, Debug)]
+struct SleeperLeagueSettings {
+    waiver_type: Option<u32>,
+    waiver_budget: Option<u32>,
+    trade_deadline: Option<u32>,
+    playoff_teams: Option<u32>,
+    playoff_week_start: Option<u32>,
+    // 0 is one week per round, 1 makes the final two weeks, 2 makes every round two weeks
+    playoff_round_type: Option<u32>,
+}
+
+// what Sleeper uses for new leagues
+const DEFAULT_PLAYOFF_TEAMS: u32 = 6;
+const DEFAULT_PLAYOFF_WEEK_START: u32 = 15;
+
+// One game in a playoff bracket.  Teams are roster IDs, and are missing until they're known.
+#[derive(Deserialize, Debug)]
+struct SleeperBracketMatch {
+    // round, starting at 1
+    r: u32,
+    // match ID, which `t1_from` and `t2_from` refer to
+    m: u32,
+    t1: Option<u32>,
+    t2: Option<u32>,
+    // winner, once it's played
+    w: Option<u32>,
+    t1_from: Option<SleeperBracketFrom>,
+    t2_from: Option<SleeperBracketFrom>,
+    // the place this game decides, if it decides one
+    p: Option<u32>,
+}
+
+// The winner or loser of another match.
+#[derive(Deserialize, Debug)]
+struct SleeperBracketFrom {
+    w: Option<u32>,
+    l: Option<u32>,
+}
+
+impl SleeperBracketFrom {
+    fn source(&self) -> Option<super::BracketSource> {
+        match (self.w, self.l) {
+            (Some(m), _) => Some(super::BracketSource::WinnerOf(m)),
+            (_, Some(m)) => Some(super::BracketSource::LoserOf(m)),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct SleeperDraft {
+    draft_id: String,
+    // pre_draft, drafting, paused or complete
+    status: String,
+    settings: SleeperDraftSettings,
+}
+
+#[derive(Deserialize, Debug)]
+struct SleeperDraftSettings {
+    rounds: u32,
+}
+
+#[derive(Deserialize, Debug)]
+struct SleeperDraftPick {
+    player_id: String,
+    // user ID, which is empty for picks made for a team without an owner
+    #[serde(default)]
+    picked_by: String,
+    // sometimes a number and sometimes a string
+    roster_id: Option<serde_json::Value>,
+    round: u32,
+    pick_no: u32,
+    is_keeper: Option<bool>,
+}
+
+#[derive(Deserialize, Debug)]
+struct SleeperTradedPick {
+    season: String,
+    round: u32,
+    // the roster the pick started with
+    roster_id: u32,
+    // and the one that has it now
+    owner_id: u32,
+}
+
+impl SleeperDraftPick {
+    fn roster_id(&self) -> Option<u32> {
+        match self.roster_id.as_ref()? {
+            serde_json::Value::Number(n) => n.as_u64().map(|n| n as u32),
+            serde_json::Value::String(s) => s.parse().ok(),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Deserialize, Debug)]
+struct SleeperMatchup {
+    roster_id: u32,
+    matchup_id: Option<u32>,
+    points: Option<f64>,
+    starters: Option<Vec<String>>,
+    players: Option<Vec<String>>,
+    players_points: Option<HashMap<String, f64>>,
 }
 
 #[derive(Deserialize, Debug)]
@@ -42,113 +201,209 @@ struct NflPlayer {
     player_id: String,
     first_name: String,
     last_name: String,
+    position: Option<String>,
     status: Option<String>,
     injury_status: Option<String>,
     injury_start_date: Option<String>,
     team: Option<String>,
+    // a number for most players, but not always
+    espn_id: Option<serde_json::Value>,
+    // lower is more relevant
+    search_rank: Option<u64>,
+}
+
+// stat name -> value, per player ID
+type StatLines = HashMap<String, HashMap<String, serde_json::Value>>;
+
+// Everything needed to project players under a league's scoring.
+struct PlayerStats {
+    scoring_settings: HashMap<String, f64>,
+    projections: Arc<StatLines>,
+    // oldest week first
+    recent: Vec<Arc<StatLines>>,
+    season_projections: Arc<StatLines>,
+    season_stats: Arc<StatLines>,
+}
+
+impl PlayerStats {
+    fn score(&self, lines: &StatLines, player_id: &str) -> Option<f64> {
+        lines
+            .get(player_id)
+            .map(|s| score_stats(&self.scoring_settings, s))
+    }
+
+    fn projection(&self, player_map: &PlayerMap, player_id: &str) -> super::PlayerProjection {
+        // the season projection covers the whole season, so take off what's already happened
+        let rest_of_season = self
+            .score(&self.season_projections, player_id)
+            .map(|p| (p - self.score(&self.season_stats, player_id).unwrap_or(0.0)).max(0.0));
+        super::PlayerProjection {
+            player: player_from_id(player_map, player_id),
+            projected: self.score(&self.projections, player_id),
+            recent: self
+                .recent
+                .iter()
+                .filter_map(|week| self.score(week, player_id))
+                .collect(),
+            rest_of_season,
+            season_points: self.score(&self.season_stats, player_id),
+        }
+    }
+}
+
+impl NflPlayer {
+    fn full_name(&self) -> String {
+        format!("{} {}", self.first_name, self.last_name)
+    }
+}
+
+type PlayerMap = HashMap<String, NflPlayer>;
+
+// (kind, season, week) of some stat lines, with no week for season totals
+type StatLinesKey = (String, String, Option<u32>);
+
+// Sleeper's whole player database.  It's big, so it's loaded once and shared by every league,
+// along with the stat lines every player lookup needs.
+pub struct SleeperPlayers {
+    player_map: RwLock<PlayerMap>,
+    stat_lines: RwLock<HashMap<StatLinesKey, (Instant, Arc<StatLines>)>>,
+}
+
+impl SleeperPlayers {
+    pub async fn load() -> Result<SleeperPlayers, Box<dyn Error>> {
+        let players = SleeperPlayers {
+            player_map: RwLock::new(HashMap::new()),
+            stat_lines: RwLock::new(HashMap::new()),
+        };
+        players.refresh().await?;
+        Ok(players)
+    }
+
+    // Re-downloads the players once the copy on disk is a day old.  Safe to call as often as
+    // you like.
+    pub async fn refresh(&self) -> Result<(), Box<dyn Error>> {
+        let stale = players_file_is_stale();
+        if stale {
+            debug!("Reloading players file from Sleeper");
+            fetch_players().await?;
+        }
+        let loaded = !self.player_map.read().unwrap().is_empty();
+        if stale || !loaded {
+            let player_map = load_players_from_file()?;
+            debug!("loaded {} players", player_map.len());
+            *self.player_map.write().unwrap() = player_map;
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    pub fn from_json(players: serde_json::Value) -> SleeperPlayers {
+        SleeperPlayers {
+            player_map: RwLock::new(serde_json::from_value(players).unwrap()),
+            stat_lines: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn player(&self, player_id: &str) -> Option<super::FantasyPlayer> {
+        let player_map = self.player_map.read().unwrap();
+        player_map
+            .get(player_id)
+            .map(|_| player_from_id(&player_map, player_id))
+    }
+
+    pub fn search(&self, name: &str) -> Option<super::FantasyPlayer> {
+        let player_map = self.player_map.read().unwrap();
+        find_player_id(&player_map, name).map(|id| player_from_id(&player_map, &id))
+    }
+
+    pub fn injury_start_date(&self, player_id: &str) -> Option<String> {
+        let player_map = self.player_map.read().unwrap();
+        player_map.get(player_id)?.injury_start_date.clone()
+    }
+
+    // Stat lines for every player, keyed by player ID.  `kind` is either "stats" for what
+    // actually happened or "projections", and leaving out the week gives season totals.  They're
+    // only downloaded again once they're an hour old.
+    async fn stat_lines(
+        &self,
+        kind: &str,
+        season: &str,
+        week_num: Option<u32>,
+    ) -> Result<Arc<StatLines>, Box<dyn Error>> {
+        let key = (kind.to_string(), season.to_string(), week_num);
+        if let Some((fetched, lines)) = self.stat_lines.read().unwrap().get(&key) {
+            if fetched.elapsed() < STAT_LINES_TTL {
+                return Ok(lines.clone());
+            }
+        }
+        let mut url = format!("{}/{}/nfl/regular/{}", SLEEPER_API_URL, kind, season);
+        if let Some(week) = week_num {
+            url = format!("{}/{}", url, week);
+        }
+        let lines = Arc::new(reqwest::get(url).await?.json::<StatLines>().await?);
+        let mut cache = self.stat_lines.write().unwrap();
+        cache.retain(|_, (fetched, _)| fetched.elapsed() < STAT_LINES_TTL);
+        cache.insert(key, (Instant::now(), lines.clone()));
+        Ok(lines)
+    }
+
+    // The same player's ID on ESPN, if Sleeper knows it.
+    pub fn espn_id(&self, player_id: &str) -> Option<String> {
+        let player_map = self.player_map.read().unwrap();
+        match player_map.get(player_id)?.espn_id.as_ref()? {
+            serde_json::Value::Number(n) => Some(n.to_string()),
+            serde_json::Value::String(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
 }
 
 struct Cache {
-    player_map: HashMap<String, NflPlayer>,
     roster_map: HashMap<String, u32>,
     users_map: HashMap<String, SleeperUser>,
 }
 
 pub struct SleeperClient {
     league_id: String,
+    players: Arc<SleeperPlayers>,
     cache: RwLock<Cache>,
+    schedule_cache: super::ScheduleCache,
 }
 
 impl SleeperClient {
-    pub async fn new(league_id: String) -> SleeperClient {
+    pub async fn new(league_id: String, players: Arc<SleeperPlayers>) -> SleeperClient {
         let cache = Cache {
-            player_map: HashMap::new(),
             roster_map: HashMap::new(),
             users_map: HashMap::new(),
         };
         let mut client = SleeperClient {
             league_id: league_id.to_owned(),
+            players,
             cache: RwLock::new(cache),
+            schedule_cache: super::ScheduleCache::default(),
         };
         client.initialize().await;
         client
     }
 
-    pub async fn get_league_details(&self) -> Result<(), Box<dyn Error>> {
-        debug!("Requesting Sleeper league details...");
-        let url = format!("{}/league/{}", SLEEPER_API_URL, self.league_id);
-        let resp = reqwest::get(url).await?.json::<serde_json::Value>().await?;
-        debug!("Got Sleeper league details:\n{:?}", resp);
-        Ok(())
-    }
-
     async fn initialize(&mut self) {
-        let mut cache = self.cache.write().unwrap();
-        self.load_players(&mut *cache)
-            .await
-            .expect("Could not initialize players list");
-        self.load_teams(&mut *cache)
+        let mut cache = Cache {
+            roster_map: HashMap::new(),
+            users_map: HashMap::new(),
+        };
+        self.load_teams(&mut cache)
             .await
             .expect("Could not initialize teams list");
+        *self.cache.get_mut().unwrap() = cache;
     }
 
-    async fn load_players(&self, cache: &mut Cache) -> Result<(), Box<dyn Error>> {
-        debug!("Loading Sleeper players map...");
-        // Sleeper asks that we only call this endpoint once a day so let's be nice and do that.
-        let reload = fs::metadata(PLAYERS_DATA_PATH)
-            .ok()
-            .map_or(true, |metadata| match metadata.modified() {
-                Ok(mt) => mt
-                    .elapsed()
-                    .ok()
-                    .map_or(true, |el| el.as_secs() > SECS_PER_DAY),
-                Err(e) => {
-                    eprintln!("Could not determine modification time. Err:\n{}", e);
-                    true
-                }
-            });
-
-        if reload {
-            debug!("Reloading players file from Sleeper");
-            self.fetch_players().await?;
-        }
-
-        self.load_players_from_file(cache);
-
-        Ok(())
-    }
-
-    async fn fetch_players(&self) -> Result<(), Box<dyn Error>> {
-        let url = format!("{}/players/nfl", SLEEPER_API_URL);
-        let resp = reqwest::get(url).await?;
-        let data_file = File::create(PLAYERS_DATA_PATH)?;
-        let mut f = BufWriter::new(data_file);
-        f.write_all(resp.text().await?.as_bytes())?;
-        Ok(())
-    }
-
-    fn load_players_from_file(&self, cache: &mut Cache) {
-        let data = fs::read_to_string(PLAYERS_DATA_PATH).expect("Unable to read data file");
-        let mut json: HashMap<String, NflPlayer> =
-            serde_json::from_str(&data).expect("Unable to parse data file");
-        debug!("loaded {} players", json.len());
-        cache.player_map.clear();
-        for (key, val) in json.drain() {
-            cache.player_map.insert(key, val);
-        }
+    async fn fetch_rosters(&self) -> Result<Vec<SleeperRoster>, Box<dyn Error>> {
+        get_league_rosters(&self.league_id).await
     }
 
     async fn load_teams(&self, cache: &mut Cache) -> Result<(), Box<dyn Error>> {
-        let rosters_url = format!("{}/league/{}/rosters", SLEEPER_API_URL, self.league_id);
-        let rosters_resp = reqwest::get(rosters_url)
-            .await?
-            .json::<Vec<SleeperRoster>>()
-            .await?;
-        let users_url = format!("{}/league/{}/users", SLEEPER_API_URL, self.league_id);
-        let users_resp = reqwest::get(users_url)
-            .await?
-            .json::<Vec<SleeperUser>>()
-            .await?;
+        let rosters_resp = self.fetch_rosters().await?;
+        let users_resp = get_league_users(&self.league_id).await?;
         debug!("rosters: {:?}, users: {:?}", rosters_resp, users_resp);
         let mut rosters_by_user: HashMap<String, SleeperRoster> = HashMap::new();
         for roster in rosters_resp {
@@ -170,35 +425,496 @@ impl SleeperClient {
         Ok(())
     }
 
-    pub async fn get_nfl_state(&self) -> Result<SleeperNflStateApiResponse, Box<dyn Error>> {
-        let state_url = format!("{}/state/nfl", SLEEPER_API_URL);
-        let state_resp = reqwest::get(state_url)
-            .await?
-            .json::<SleeperNflStateApiResponse>()
+    async fn fetch_league(&self) -> Result<SleeperLeague, Box<dyn Error>> {
+        get_league(&self.league_id).await
+    }
+
+    async fn fetch_player_stats(
+        &self,
+        league: &SleeperLeague,
+        week_num: u32,
+    ) -> Result<PlayerStats, Box<dyn Error>> {
+        let season = &league.season;
+        let players = &self.players;
+        let mut recent = vec![];
+        for week in week_num.saturating_sub(super::RECENT_WEEKS).max(1)..week_num {
+            recent.push(players.stat_lines("stats", season, Some(week)).await?);
+        }
+        let projections = players
+            .stat_lines("projections", season, Some(week_num))
             .await?;
+        let season_projections = players.stat_lines("projections", season, None).await?;
+        let season_stats = players.stat_lines("stats", season, None).await?;
+        Ok(PlayerStats {
+            scoring_settings: league.scoring_settings.clone(),
+            projections,
+            recent,
+            season_projections,
+            season_stats,
+        })
+    }
+
+    async fn fetch_matchups(&self, week_num: u32) -> Result<Vec<SleeperMatchup>, Box<dyn Error>> {
+        get_league_matchups(&self.league_id, week_num).await
+    }
+
+    // One week's matchups, pairing up rosters by matchup ID.
+    async fn fetch_week_matchups(
+        &self,
+        week_num: u32,
+    ) -> Result<Vec<super::FantasyMatchup>, Box<dyn Error>> {
+        let matchups = self.fetch_matchups(week_num).await?;
+        let cache = self.cache.read().unwrap();
+        Ok(week_matchups(week_num, &matchups, |r| {
+            SleeperClient::team_for_roster(&cache, r)
+        }))
+    }
+
+    // `points` is what each roster scored each week, keyed by (week, roster ID).
+    fn bracket_from_matches(
+        cache: &Cache,
+        kind: super::BracketKind,
+        matches: &[SleeperBracketMatch],
+        round_weeks: &[Vec<u32>],
+        points: &HashMap<(u32, u32), f64>,
+    ) -> super::Bracket {
+        let games = matches
+            .iter()
+            .map(|m| {
+                let week_nums = round_weeks
+                    .get(m.r as usize - 1)
+                    .cloned()
+                    .unwrap_or_default();
+                let side = |roster_id: Option<u32>, from: &Option<SleeperBracketFrom>| {
+                    let scores: Vec<f64> = roster_id.map_or(vec![], |r| {
+                        week_nums
+                            .iter()
+                            .filter_map(|w| points.get(&(*w, r)).copied())
+                            .collect()
+                    });
+                    super::BracketTeam {
+                        team: roster_id.and_then(|r| SleeperClient::team_for_roster(cache, r)),
+                        seed: None,
+                        from: from.as_ref().and_then(SleeperBracketFrom::source),
+                        score: if scores.is_empty() {
+                            None
+                        } else {
+                            Some(scores.iter().sum())
+                        },
+                    }
+                };
+                super::BracketGame {
+                    id: m.m,
+                    round: m.r,
+                    team1: side(m.t1, &m.t1_from),
+                    team2: side(m.t2, &m.t2_from),
+                    week_nums: week_nums.clone(),
+                    winner: m
+                        .w
+                        .and_then(|r| SleeperClient::team_for_roster(cache, r))
+                        .map(|t| t.id),
+                    place: m.p,
+                }
+            })
+            .collect();
+        super::Bracket { kind, games }
+    }
+
+    fn team_from_user(user: &SleeperUser) -> super::FantasyTeam {
+        super::FantasyTeam {
+            id: user.user_id.clone(),
+            team_name: user
+                .metadata
+                .team_name
+                .clone()
+                .unwrap_or_else(|| user.display_name.clone()),
+            owner_name: user.display_name.clone(),
+            avatar_url: user
+                .avatar
+                .as_ref()
+                .map(|a| format!("{}/{}", SLEEPER_AVATAR_URL, a)),
+        }
+    }
+
+    fn team_for_roster(cache: &Cache, roster_id: u32) -> Option<super::FantasyTeam> {
+        cache
+            .roster_map
+            .iter()
+            .find(|(_, r)| **r == roster_id)
+            .and_then(|(user_id, _)| cache.users_map.get(user_id))
+            .map(SleeperClient::team_from_user)
+    }
+}
+
+// Sleeper asks that we only download the players once a day, so let's be nice and do that.
+fn players_file_is_stale() -> bool {
+    fs::metadata(PLAYERS_DATA_PATH)
+        .ok()
+        .is_none_or(|metadata| match metadata.modified() {
+            Ok(mt) => mt
+                .elapsed()
+                .ok()
+                .is_none_or(|el| el.as_secs() > SECS_PER_DAY),
+            Err(e) => {
+                eprintln!("Could not determine modification time. Err:\n{}", e);
+                true
+            }
+        })
+}
+
+async fn fetch_players() -> Result<(), Box<dyn Error>> {
+    let url = format!("{}/players/nfl", SLEEPER_API_URL);
+    let resp = reqwest::get(url).await?;
+    let data_file = File::create(PLAYERS_DATA_PATH)?;
+    let mut f = BufWriter::new(data_file);
+    f.write_all(resp.text().await?.as_bytes())?;
+    Ok(())
+}
+
+fn load_players_from_file() -> Result<PlayerMap, Box<dyn Error>> {
+    let data = fs::read_to_string(PLAYERS_DATA_PATH)?;
+    Ok(serde_json::from_str(&data)?)
+}
+
+// The fantasy-relevant player whose name best matches a search.
+fn find_player_id(player_map: &PlayerMap, name: &str) -> Option<String> {
+    let candidates: Vec<(String, u64, &String)> = player_map
+        .values()
+        .filter(|p| {
+            p.position
+                .as_deref()
+                .is_some_and(|pos| players::FANTASY_POSITIONS.contains(&pos))
+        })
+        .map(|p| {
+            let rank = p.search_rank.unwrap_or(u64::MAX);
+            (p.full_name(), rank, &p.player_id)
+        })
+        .collect();
+    players::best_match(
+        name,
+        candidates
+            .iter()
+            .map(|(n, rank, id)| (n.as_str(), *rank, *id)),
+    )
+    .cloned()
+}
+
+fn player_from_id(player_map: &PlayerMap, player_id: &str) -> super::FantasyPlayer {
+    match player_map.get(player_id) {
+        Some(p) => super::FantasyPlayer {
+            id: p.player_id.clone(),
+            name: p.full_name(),
+            position: p.position.clone().unwrap_or_default(),
+            nfl_team: p.team.clone(),
+            injury_status: p.injury_status.clone(),
+        },
+        None if player_id == super::EMPTY_SLOT_ID => super::FantasyPlayer::empty_slot(""),
+        None => super::FantasyPlayer {
+            id: player_id.to_string(),
+            name: player_id.to_string(),
+            position: "".to_string(),
+            nfl_team: None,
+            injury_status: None,
+        },
+    }
+}
+
+// A headshot for a player from Sleeper's player map, or a logo for a team defense.
+pub fn player_image_url(player: &super::FantasyPlayer) -> String {
+    match (player.position.as_str(), &player.nfl_team) {
+        ("DEF", Some(team)) => format!("{}/{}.png", SLEEPER_TEAM_IMAGE_URL, team.to_lowercase()),
+        _ => format!("{}/{}.jpg", SLEEPER_PLAYER_IMAGE_URL, player.id),
+    }
+}
+
+// The NFL state isn't league-specific, so this is shared by every league type.
+pub async fn get_nfl_state() -> Result<SleeperNflStateApiResponse, Box<dyn Error>> {
+    let state_url = format!("{}/state/nfl", SLEEPER_API_URL);
+    let state_resp = reqwest::get(state_url)
+        .await?
+        .json::<SleeperNflStateApiResponse>()
+        .await?;
+
+    Ok(state_resp)
+}
+
+async fn get_league(league_id: &str) -> Result<SleeperLeague, Box<dyn Error>> {
+    let url = format!("{}/league/{}", SLEEPER_API_URL, league_id);
+    let resp = reqwest::get(url).await?.json::<SleeperLeague>().await?;
+    Ok(resp)
+}
+
+async fn get_league_users(league_id: &str) -> Result<Vec<SleeperUser>, Box<dyn Error>> {
+    let users_url = format!("{}/league/{}/users", SLEEPER_API_URL, league_id);
+    let users_resp = reqwest::get(users_url)
+        .await?
+        .json::<Vec<SleeperUser>>()
+        .await?;
+    Ok(users_resp)
+}
+
+async fn get_league_rosters(league_id: &str) -> Result<Vec<SleeperRoster>, Box<dyn Error>> {
+    let rosters_url = format!("{}/league/{}/rosters", SLEEPER_API_URL, league_id);
+    let rosters_resp = reqwest::get(rosters_url)
+        .await?
+        .json::<Vec<SleeperRoster>>()
+        .await?;
+    Ok(rosters_resp)
+}
+
+async fn get_league_matchups(
+    league_id: &str,
+    week_num: u32,
+) -> Result<Vec<SleeperMatchup>, Box<dyn Error>> {
+    let matchups_url = format!(
+        "{}/league/{}/matchups/{}",
+        SLEEPER_API_URL, league_id, week_num
+    );
+    let matchups_resp = reqwest::get(matchups_url)
+        .await?
+        .json::<Vec<SleeperMatchup>>()
+        .await?;
+    debug!("matchups for week {}: {:?}", week_num, matchups_resp);
+    Ok(matchups_resp)
+}
+
+// Newest first.
+async fn get_league_drafts(league_id: &str) -> Result<Vec<SleeperDraft>, Box<dyn Error>> {
+    let url = format!("{}/league/{}/drafts", SLEEPER_API_URL, league_id);
+    let resp = reqwest::get(url).await?.json::<Vec<SleeperDraft>>().await?;
+    Ok(resp)
+}
+
+async fn get_draft_picks(draft_id: &str) -> Result<Vec<SleeperDraftPick>, Box<dyn Error>> {
+    let url = format!("{}/draft/{}/picks", SLEEPER_API_URL, draft_id);
+    let resp = reqwest::get(url)
+        .await?
+        .json::<Vec<SleeperDraftPick>>()
+        .await?;
+    Ok(resp)
+}
+
+async fn get_traded_picks(league_id: &str) -> Result<Vec<SleeperTradedPick>, Box<dyn Error>> {
+    let url = format!("{}/league/{}/traded_picks", SLEEPER_API_URL, league_id);
+    let resp = reqwest::get(url)
+        .await?
+        .json::<Vec<SleeperTradedPick>>()
+        .await?;
+    Ok(resp)
+}
+
+// `kind` is "winners_bracket" or "losers_bracket".  Empty until the playoffs are set.
+async fn get_bracket(
+    league_id: &str,
+    kind: &str,
+) -> Result<Vec<SleeperBracketMatch>, Box<dyn Error>> {
+    let url = format!("{}/league/{}/{}", SLEEPER_API_URL, league_id, kind);
+    let resp = reqwest::get(url)
+        .await?
+        .json::<Option<Vec<SleeperBracketMatch>>>()
+        .await?;
+    Ok(resp.unwrap_or_default())
+}
+
+// Each week's rosters come back one by one; the two with the same matchup ID played each other.
+fn pair_matchups(matchups: &[SleeperMatchup]) -> Vec<(&SleeperMatchup, &SleeperMatchup)> {
+    let mut by_matchup_id: HashMap<u32, Vec<&SleeperMatchup>> = HashMap::new();
+    for m in matchups.iter() {
+        if let Some(id) = m.matchup_id {
+            by_matchup_id.entry(id).or_default().push(m);
+        }
+    }
+    let mut matchup_ids: Vec<&u32> = by_matchup_id.keys().collect();
+    matchup_ids.sort();
+    matchup_ids
+        .into_iter()
+        .filter_map(|id| match by_matchup_id[id].as_slice() {
+            [one, two] => Some((*one, *two)),
+            _ => None,
+        })
+        .collect()
+}
 
-        Ok(state_resp)
+// A week's games between teams `team` can find by roster ID.
+fn week_matchups(
+    week_num: u32,
+    matchups: &[SleeperMatchup],
+    team: impl Fn(u32) -> Option<super::FantasyTeam>,
+) -> Vec<super::FantasyMatchup> {
+    pair_matchups(matchups)
+        .into_iter()
+        .filter_map(|(one, two)| {
+            Some(super::FantasyMatchup {
+                team1: team(one.roster_id)?,
+                team2: team(two.roster_id)?,
+                score1: one.points,
+                score2: two.points,
+                week_num,
+            })
+        })
+        .collect()
+}
+
+// A regular season week, which counts as played once the NFL is past `played_through`.
+fn schedule_week(
+    week_num: u32,
+    matchups: &[SleeperMatchup],
+    played_through: u32,
+    team: impl Fn(u32) -> Option<super::FantasyTeam>,
+) -> super::ScheduleWeek {
+    super::ScheduleWeek {
+        week_num,
+        matchups: week_matchups(week_num, matchups, team),
+        played: week_num <= played_through,
     }
 }
 
+// Each game over one or more weeks as (roster ID, roster ID, points, points), with the points
+// summed across the weeks.  In the playoffs, `only` limits it to games between those rosters.
+fn round_scores(
+    weeks: &[Vec<SleeperMatchup>],
+    only: Option<&HashSet<(u32, u32)>>,
+) -> Vec<(u32, u32, f64, f64)> {
+    let mut games: Vec<(u32, u32, f64, f64)> = vec![];
+    for week in weeks.iter() {
+        for (one, two) in pair_matchups(week) {
+            if only.is_some_and(|o| !o.contains(&(one.roster_id, two.roster_id))) {
+                continue;
+            }
+            let (score1, score2) = match (one.points, two.points) {
+                (Some(s1), Some(s2)) => (s1, s2),
+                _ => continue,
+            };
+            match games.iter_mut().find(|g| {
+                (g.0, g.1) == (one.roster_id, two.roster_id)
+                    || (g.0, g.1) == (two.roster_id, one.roster_id)
+            }) {
+                Some(game) if game.0 == one.roster_id => {
+                    game.2 += score1;
+                    game.3 += score2;
+                }
+                Some(game) => {
+                    game.2 += score2;
+                    game.3 += score1;
+                }
+                None => games.push((one.roster_id, two.roster_id, score1, score2)),
+            }
+        }
+    }
+    games
+}
+
+fn league_settings(league: SleeperLeague) -> super::LeagueSettings {
+    let settings = &league.settings;
+    let playoff_teams = settings.playoff_teams.unwrap_or(DEFAULT_PLAYOFF_TEAMS);
+    let waiver_type = match settings.waiver_type {
+        Some(FAAB_WAIVER_TYPE) => super::WaiverType::Faab,
+        Some(ROLLING_WAIVER_TYPE) => super::WaiverType::Rolling,
+        _ => super::WaiverType::ReverseStandings,
+    };
+    super::LeagueSettings {
+        status: match league.status.as_str() {
+            "pre_draft" => super::LeagueStatus::PreDraft,
+            "drafting" => super::LeagueStatus::Drafting,
+            "complete" => super::LeagueStatus::Complete,
+            _ => super::LeagueStatus::InSeason,
+        },
+        teams: league.total_rosters,
+        playoff_teams,
+        playoff_week_start: settings
+            .playoff_week_start
+            .unwrap_or(DEFAULT_PLAYOFF_WEEK_START),
+        playoff_round_weeks: playoff_round_weeks(settings, super::playoff_rounds(playoff_teams)),
+        faab_budget: settings
+            .waiver_budget
+            .filter(|_| waiver_type == super::WaiverType::Faab),
+        waiver_type,
+        trade_deadline: settings
+            .trade_deadline
+            .filter(|w| *w != NO_TRADE_DEADLINE)
+            .map(super::TradeDeadline::Week),
+        season: league.season,
+        roster_positions: league.roster_positions,
+        scoring: league.scoring_settings,
+        previous_league_id: league.previous_league_id.filter(|p| p != "0"),
+    }
+}
+
+// The weeks each playoff round is played in, first round first.
+fn playoff_round_weeks(settings: &SleeperLeagueSettings, rounds: u32) -> Vec<Vec<u32>> {
+    let mut week_num = settings
+        .playoff_week_start
+        .unwrap_or(DEFAULT_PLAYOFF_WEEK_START);
+    (1..=rounds)
+        .map(|round| {
+            let weeks = match settings.playoff_round_type {
+                Some(1) if round == rounds => 2,
+                Some(2) => 2,
+                _ => 1,
+            };
+            let round_weeks = (week_num..week_num + weeks).collect();
+            week_num += weeks;
+            round_weeks
+        })
+        .collect()
+}
+
+// How many weeks of a league's regular season are over, going by where the NFL is.
+fn weeks_played(state: &SleeperNflStateApiResponse, league_season: &str, last_week: u32) -> u32 {
+    // seasons are years, so they compare the same as strings
+    match state.season.as_str().cmp(league_season) {
+        std::cmp::Ordering::Greater => return last_week,
+        std::cmp::Ordering::Less => return 0,
+        std::cmp::Ordering::Equal => {}
+    }
+    match state.season_type.as_str() {
+        "regular" => state.week.saturating_sub(1).min(last_week),
+        "post" => last_week,
+        _ => 0,
+    }
+}
+
+// The most added or dropped players across all of Sleeper.  `kind` is "add" or "drop".
+pub async fn get_trending(
+    kind: &str,
+    lookback_hours: u32,
+    limit: u32,
+) -> Result<Vec<SleeperTrendingPlayer>, Box<dyn Error>> {
+    let url = format!(
+        "{}/players/nfl/trending/{}?lookback_hours={}&limit={}",
+        SLEEPER_API_URL, kind, lookback_hours, limit
+    );
+    let resp = reqwest::get(url)
+        .await?
+        .json::<Vec<SleeperTrendingPlayer>>()
+        .await?;
+    Ok(resp)
+}
+
+// Applies a league's scoring settings to a stat line.
+pub fn score_stats(
+    scoring_settings: &HashMap<String, f64>,
+    stats: &HashMap<String, serde_json::Value>,
+) -> f64 {
+    stats
+        .iter()
+        .filter_map(|(k, v)| Some(v.as_f64()? * scoring_settings.get(k)?))
+        .sum()
+}
+
+fn sleeper_points(whole: Option<u32>, decimal: Option<u32>) -> f64 {
+    whole.unwrap_or(0) as f64 + decimal.unwrap_or(0) as f64 / 100.0
+}
+
 #[async_trait]
 impl super::FantasyClient for SleeperClient {
     async fn get_teams(&self) -> Result<Vec<super::FantasyTeam>, Box<dyn Error>> {
-        let mut teams: Vec<super::FantasyTeam> = vec![];
-
         let cache = self.cache.read().unwrap();
-        for sleeper_team in cache.users_map.values() {
-            let team = super::FantasyTeam {
-                id: sleeper_team.user_id.clone(),
-                team_name: sleeper_team
-                    .metadata
-                    .team_name
-                    .clone()
-                    .unwrap_or(sleeper_team.display_name.clone()),
-                owner_name: sleeper_team.display_name.clone(),
-            };
-            teams.push(team);
-        }
+        let teams = cache
+            .users_map
+            .values()
+            .map(SleeperClient::team_from_user)
+            .collect();
 
         Ok(teams)
     }
@@ -207,11 +923,657 @@ impl super::FantasyClient for SleeperClient {
         &self,
         week_num: Option<u32>,
     ) -> Result<Vec<super::FantasyMatchup>, Box<dyn Error>> {
-        let matchups: Vec<super::FantasyMatchup> = vec![];
         let req_week_num = match week_num {
             Some(n) => n,
-            None => self.get_nfl_state().await?.week,
+            None => get_nfl_state().await?.week,
+        };
+        self.fetch_week_matchups(req_week_num).await
+    }
+
+    async fn get_schedule(&self) -> Result<super::Schedule, Box<dyn Error>> {
+        if let Some(schedule) = self.schedule_cache.complete() {
+            return Ok(schedule);
+        }
+        let league = self.fetch_league().await?;
+        let state = get_nfl_state().await?;
+        let last_week = league
+            .settings
+            .playoff_week_start
+            .unwrap_or(DEFAULT_PLAYOFF_WEEK_START)
+            .saturating_sub(1);
+        let played_through = weeks_played(&state, &league.season, last_week);
+        let mut weeks = vec![];
+        for week_num in 1..=last_week {
+            if let Some(week) = self.schedule_cache.week(week_num) {
+                weeks.push(week);
+                continue;
+            }
+            let matchups = self.fetch_matchups(week_num).await?;
+            let cache = self.cache.read().unwrap();
+            weeks.push(schedule_week(week_num, &matchups, played_through, |r| {
+                SleeperClient::team_for_roster(&cache, r)
+            }));
+        }
+        let schedule = super::Schedule { weeks };
+        self.schedule_cache.store(&schedule);
+        Ok(schedule)
+    }
+
+    async fn get_standings(&self) -> Result<Vec<super::FantasyStanding>, Box<dyn Error>> {
+        let rosters = self.fetch_rosters().await?;
+        let cache = self.cache.read().unwrap();
+        let mut standings: Vec<super::FantasyStanding> = vec![];
+        for roster in rosters {
+            if let Some(team) = SleeperClient::team_for_roster(&cache, roster.roster_id) {
+                let settings = &roster.settings;
+                standings.push(super::FantasyStanding {
+                    team,
+                    wins: settings.wins,
+                    losses: settings.losses,
+                    ties: settings.ties,
+                    points_for: sleeper_points(settings.fpts, settings.fpts_decimal),
+                    points_against: sleeper_points(
+                        settings.fpts_against,
+                        settings.fpts_against_decimal,
+                    ),
+                });
+            }
+        }
+        super::sort_standings(&mut standings);
+        Ok(standings)
+    }
+
+    async fn get_rosters(&self) -> Result<Vec<super::FantasyRoster>, Box<dyn Error>> {
+        let rosters = self.fetch_rosters().await?;
+        let league = self.fetch_league().await?;
+        let cache = self.cache.read().unwrap();
+        let player_map = self.players.player_map.read().unwrap();
+        let mut ret: Vec<super::FantasyRoster> = vec![];
+        for roster in rosters {
+            let team = match SleeperClient::team_for_roster(&cache, roster.roster_id) {
+                Some(t) => t,
+                None => continue,
+            };
+            let starter_ids = roster.starters.unwrap_or_default();
+            let starters = starter_ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| match league.roster_positions.get(i) {
+                    Some(slot) if id == super::EMPTY_SLOT_ID => {
+                        super::FantasyPlayer::empty_slot(slot)
+                    }
+                    _ => player_from_id(&player_map, id),
+                })
+                .collect();
+            let reserve_ids = roster.reserve.unwrap_or_default();
+            let taxi_ids = roster.taxi.unwrap_or_default();
+            let bench = roster
+                .players
+                .unwrap_or_default()
+                .iter()
+                .filter(|id| {
+                    !starter_ids.contains(id) && !reserve_ids.contains(id) && !taxi_ids.contains(id)
+                })
+                .map(|id| player_from_id(&player_map, id))
+                .collect();
+            let players = |ids: &[String]| {
+                ids.iter()
+                    .map(|id| player_from_id(&player_map, id))
+                    .collect()
+            };
+            ret.push(super::FantasyRoster {
+                team,
+                starters,
+                bench,
+                reserve: players(&reserve_ids),
+                taxi: players(&taxi_ids),
+            });
+        }
+        Ok(ret)
+    }
+
+    async fn get_lineups(
+        &self,
+        week_num: u32,
+    ) -> Result<Vec<super::FantasyLineup>, Box<dyn Error>> {
+        let matchups_resp = self.fetch_matchups(week_num).await?;
+        let league = self.fetch_league().await?;
+        let projections = self
+            .players
+            .stat_lines("projections", &league.season, Some(week_num))
+            .await?;
+        let cache = self.cache.read().unwrap();
+        let player_map = self.players.player_map.read().unwrap();
+        let mut lineups: Vec<super::FantasyLineup> = vec![];
+        for m in matchups_resp {
+            let team = match SleeperClient::team_for_roster(&cache, m.roster_id) {
+                Some(t) => t,
+                None => continue,
+            };
+            let points = m.players_points.unwrap_or_default();
+            let score = |id: &String| super::PlayerScore {
+                player: player_from_id(&player_map, id),
+                points: points.get(id).copied().unwrap_or(0.0),
+                projected: Some(
+                    projections
+                        .get(id)
+                        .map_or(0.0, |stats| score_stats(&league.scoring_settings, stats)),
+                ),
+            };
+            let starter_ids = m.starters.unwrap_or_default();
+            let starters = starter_ids.iter().map(score).collect();
+            let bench = m
+                .players
+                .unwrap_or_default()
+                .iter()
+                .filter(|id| !starter_ids.contains(id))
+                .map(score)
+                .collect();
+            lineups.push(super::FantasyLineup {
+                team,
+                starters,
+                bench,
+            });
+        }
+        Ok(lineups)
+    }
+
+    async fn search_players(
+        &self,
+        names: &[String],
+        week_num: u32,
+    ) -> Result<Vec<Option<super::PlayerProjection>>, Box<dyn Error>> {
+        let league = self.fetch_league().await?;
+        let stats = self.fetch_player_stats(&league, week_num).await?;
+
+        let player_map = self.players.player_map.read().unwrap();
+        let found = names
+            .iter()
+            .map(|name| {
+                let id = find_player_id(&player_map, name)?;
+                Some(stats.projection(&player_map, &id))
+            })
+            .collect();
+        Ok(found)
+    }
+
+    async fn get_free_agents(
+        &self,
+        week_num: u32,
+    ) -> Result<Vec<super::PlayerProjection>, Box<dyn Error>> {
+        let rosters = self.fetch_rosters().await?;
+        let league = self.fetch_league().await?;
+        let stats = self.fetch_player_stats(&league, week_num).await?;
+        let rostered: HashSet<String> = rosters
+            .into_iter()
+            .flat_map(|r| r.players.unwrap_or_default())
+            .collect();
+
+        let player_map = self.players.player_map.read().unwrap();
+        let free_agents = player_map
+            .values()
+            .filter(|p| !rostered.contains(&p.player_id))
+            .filter(|p| {
+                p.position
+                    .as_deref()
+                    .is_some_and(|pos| players::FANTASY_POSITIONS.contains(&pos))
+            })
+            // team defenses don't have a status
+            .filter(|p| p.team.is_some() && p.status.as_deref().is_none_or(|s| s == "Active"))
+            .map(|p| stats.projection(&player_map, &p.player_id))
+            .collect();
+        Ok(free_agents)
+    }
+
+    async fn get_faab_balances(&self) -> Result<Vec<super::FaabBalance>, Box<dyn Error>> {
+        let budget = match league_settings(self.fetch_league().await?).faab_budget {
+            Some(b) => b,
+            None => return Ok(vec![]),
+        };
+        let rosters = self.fetch_rosters().await?;
+        let cache = self.cache.read().unwrap();
+        Ok(rosters
+            .iter()
+            .filter_map(|r| {
+                Some(super::FaabBalance {
+                    team: SleeperClient::team_for_roster(&cache, r.roster_id)?,
+                    remaining: budget.saturating_sub(r.settings.waiver_budget_used.unwrap_or(0)),
+                    budget,
+                })
+            })
+            .collect())
+    }
+
+    // Sleeper always breaks ties in the standings with points scored.
+    async fn get_playoff_format(&self) -> Result<super::PlayoffFormat, Box<dyn Error>> {
+        let settings = league_settings(self.fetch_league().await?);
+        Ok(super::PlayoffFormat::new(
+            settings.playoff_teams,
+            super::Tiebreaker::PointsFor,
+        ))
+    }
+
+    async fn get_league_settings(&self) -> Result<super::LeagueSettings, Box<dyn Error>> {
+        Ok(league_settings(self.fetch_league().await?))
+    }
+
+    async fn get_draft(&self) -> Result<Option<super::Draft>, Box<dyn Error>> {
+        let drafts = get_league_drafts(&self.league_id).await?;
+        let draft = match drafts.into_iter().next() {
+            Some(d) => d,
+            None => return Ok(None),
+        };
+        let status = match draft.status.as_str() {
+            "pre_draft" => super::DraftStatus::Scheduled,
+            "complete" => super::DraftStatus::Complete,
+            _ => super::DraftStatus::Drafting,
+        };
+        let mut picks = if status == super::DraftStatus::Scheduled {
+            vec![]
+        } else {
+            get_draft_picks(&draft.draft_id).await?
+        };
+        picks.sort_by_key(|p| p.pick_no);
+
+        let cache = self.cache.read().unwrap();
+        let player_map = self.players.player_map.read().unwrap();
+        let picks = picks
+            .iter()
+            .filter_map(|p| {
+                let team = match cache.users_map.get(&p.picked_by) {
+                    Some(user) => SleeperClient::team_from_user(user),
+                    None => SleeperClient::team_for_roster(&cache, p.roster_id()?)?,
+                };
+                Some(super::DraftPick {
+                    pick_no: p.pick_no,
+                    round: p.round,
+                    team,
+                    player: player_from_id(&player_map, &p.player_id),
+                    keeper: p.is_keeper.unwrap_or(false),
+                })
+            })
+            .collect();
+        Ok(Some(super::Draft {
+            id: draft.draft_id,
+            status,
+            rounds: draft.settings.rounds,
+            picks,
+        }))
+    }
+
+    async fn get_traded_picks(&self) -> Result<Vec<super::TradedPick>, Box<dyn Error>> {
+        let traded = get_traded_picks(&self.league_id).await?;
+        let cache = self.cache.read().unwrap();
+        Ok(traded
+            .iter()
+            // picks that were traded back home again are still listed
+            .filter(|p| p.owner_id != p.roster_id)
+            .filter_map(|p| {
+                Some(super::TradedPick {
+                    season: p.season.clone(),
+                    round: p.round,
+                    original_team: SleeperClient::team_for_roster(&cache, p.roster_id)?,
+                    owner: SleeperClient::team_for_roster(&cache, p.owner_id)?,
+                })
+            })
+            .collect())
+    }
+
+    async fn get_season_points(
+        &self,
+        player_ids: &[String],
+    ) -> Result<HashMap<String, f64>, Box<dyn Error>> {
+        let league = self.fetch_league().await?;
+        let stats = self
+            .players
+            .stat_lines("stats", &league.season, None)
+            .await?;
+        Ok(player_ids
+            .iter()
+            .filter_map(|id| {
+                let line = stats.get(id)?;
+                Some((id.clone(), score_stats(&league.scoring_settings, line)))
+            })
+            .collect())
+    }
+
+    async fn get_brackets(&self) -> Result<Vec<super::Bracket>, Box<dyn Error>> {
+        let winners = get_bracket(&self.league_id, "winners_bracket").await?;
+        if winners.is_empty() {
+            return Ok(vec![]);
+        }
+        let losers = get_bracket(&self.league_id, "losers_bracket").await?;
+        let league = self.fetch_league().await?;
+        let state = get_nfl_state().await?;
+
+        let rounds = winners.iter().chain(losers.iter()).map(|m| m.r).max();
+        let round_weeks = playoff_round_weeks(&league.settings, rounds.unwrap_or(0));
+        let mut points = HashMap::new();
+        for week_num in round_weeks.iter().flatten() {
+            // weeks that haven't started have every score at 0
+            if state.season == league.season && *week_num > state.week {
+                continue;
+            }
+            for m in self.fetch_matchups(*week_num).await? {
+                if let Some(p) = m.points {
+                    points.insert((*week_num, m.roster_id), p);
+                }
+            }
+        }
+
+        let cache = self.cache.read().unwrap();
+        let mut brackets = vec![SleeperClient::bracket_from_matches(
+            &cache,
+            super::BracketKind::Winners,
+            &winners,
+            &round_weeks,
+            &points,
+        )];
+        if !losers.is_empty() {
+            brackets.push(SleeperClient::bracket_from_matches(
+                &cache,
+                super::BracketKind::Consolation,
+                &losers,
+                &round_weeks,
+                &points,
+            ));
+        }
+        Ok(brackets)
+    }
+
+    // Sleeper makes a new league each season that points back to the last one.
+    async fn get_seasons(&self) -> Result<Vec<super::LeagueSeason>, Box<dyn Error>> {
+        let mut seasons = vec![];
+        let mut league_id = Some(self.league_id.clone());
+        while let Some(id) = league_id {
+            let league = get_league(&id).await?;
+            seasons.push(super::LeagueSeason {
+                season: league.season,
+                league_id: id,
+            });
+            // the first season's is missing or "0"
+            league_id = league.previous_league_id.filter(|p| p != "0");
+        }
+        Ok(seasons)
+    }
+
+    async fn get_season_results(
+        &self,
+        season: &super::LeagueSeason,
+    ) -> Result<super::SeasonResults, Box<dyn Error>> {
+        let league_id = &season.league_id;
+        let league = get_league(league_id).await?;
+        let users = get_league_users(league_id).await?;
+        let rosters = get_league_rosters(league_id).await?;
+        let bracket = get_bracket(league_id, "winners_bracket").await?;
+        let state = get_nfl_state().await?;
+
+        let playoff_start = league
+            .settings
+            .playoff_week_start
+            .unwrap_or(DEFAULT_PLAYOFF_WEEK_START);
+        let rounds = bracket.iter().map(|m| m.r).max().unwrap_or(0);
+        let round_weeks = playoff_round_weeks(&league.settings, rounds);
+        let last_week = round_weeks
+            .last()
+            .and_then(|weeks| weeks.last().copied())
+            .unwrap_or(playoff_start.saturating_sub(1));
+        let played_through = weeks_played(&state, &league.season, last_week);
+        let teams: HashMap<u32, super::PastTeam> = rosters
+            .iter()
+            .filter_map(|r| {
+                let team =
+                    SleeperClient::team_from_user(users.iter().find(|u| u.user_id == r.owner_id)?);
+                Some((
+                    r.roster_id,
+                    super::PastTeam {
+                        owner_id: team.id,
+                        owner_name: team.owner_name,
+                        team_name: team.team_name,
+                    },
+                ))
+            })
+            .collect();
+        // playoff weeks have consolation games too, which aren't in the winners bracket
+        let playoff_games: HashSet<(u32, u32)> = bracket
+            .iter()
+            .filter_map(|m| Some((m.t1?, m.t2?)))
+            .flat_map(|(a, b)| [(a, b), (b, a)])
+            .collect();
+        let championship = bracket
+            .iter()
+            .find(|m| m.p == Some(1))
+            .and_then(|m| Some((m.t1?, m.t2?)));
+
+        let mut games: Vec<(u32, Vec<(u32, u32, f64, f64)>)> = vec![];
+        for week_num in 1..playoff_start.min(played_through + 1) {
+            let week = get_league_matchups(league_id, week_num).await?;
+            games.push((week_num, round_scores(&[week], None)));
+        }
+        // a round that spans more than one week is a single game, once all of it's been played
+        for weeks in round_weeks.iter() {
+            let last = match weeks.last() {
+                Some(w) if *w <= played_through => *w,
+                _ => break,
+            };
+            let mut round = vec![];
+            for week_num in weeks.iter() {
+                round.push(get_league_matchups(league_id, *week_num).await?);
+            }
+            games.push((last, round_scores(&round, Some(&playoff_games))));
+        }
+
+        let mut matchups = vec![];
+        for (week_num, week_games) in games {
+            for (one, two, score1, score2) in week_games {
+                if let (Some(team1), Some(team2)) = (teams.get(&one), teams.get(&two)) {
+                    matchups.push(super::PastMatchup {
+                        season: league.season.clone(),
+                        week_num,
+                        team1: team1.clone(),
+                        team2: team2.clone(),
+                        score1,
+                        score2,
+                        playoff: week_num >= playoff_start,
+                        championship: week_num >= playoff_start
+                            && championship
+                                .is_some_and(|(a, b)| (one, two) == (a, b) || (one, two) == (b, a)),
+                    });
+                }
+            }
+        }
+        Ok(super::SeasonResults {
+            matchups,
+            complete: rounds > 0 && played_through == last_week,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::{FantasyTeam, LeagueStatus, Schedule, TradeDeadline, WaiverType};
+
+    fn matchup(roster_id: u32, matchup_id: u32, points: f64) -> SleeperMatchup {
+        SleeperMatchup {
+            roster_id,
+            matchup_id: Some(matchup_id),
+            points: Some(points),
+            starters: None,
+            players: None,
+            players_points: None,
+        }
+    }
+
+    #[test]
+    fn two_week_rounds_are_one_game() {
+        let week1 = vec![
+            matchup(1, 1, 100.0),
+            matchup(2, 1, 90.0),
+            matchup(3, 2, 80.0),
+            matchup(4, 2, 70.0),
+        ];
+        // matchup IDs change from week to week
+        let week2 = vec![
+            matchup(2, 1, 120.0),
+            matchup(1, 1, 95.0),
+            matchup(3, 2, 60.0),
+            matchup(4, 2, 75.0),
+        ];
+        let final_only: HashSet<(u32, u32)> = vec![(1, 2), (2, 1)].into_iter().collect();
+        let games = round_scores(&[week1, week2], Some(&final_only));
+        assert_eq!(games, vec![(1, 2, 195.0, 210.0)]);
+    }
+
+    fn nfl_state(season: &str, season_type: &str, week: u32) -> SleeperNflStateApiResponse {
+        serde_json::from_value(serde_json::json!({
+            "week": week,
+            "season_type": season_type,
+            "season_start_date": "2021-09-09",
+            "season": season,
+            "previous_season": "2020",
+            "leg": week,
+            "league_season": season,
+            "league_create_season": season,
+            "display_week": week
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn weeks_played_by_nfl_state() {
+        // a week is over once Sleeper has moved on to the next one
+        assert_eq!(
+            weeks_played(&nfl_state("2021", "regular", 1), "2021", 14),
+            0
+        );
+        assert_eq!(
+            weeks_played(&nfl_state("2021", "regular", 6), "2021", 14),
+            5
+        );
+        assert_eq!(
+            weeks_played(&nfl_state("2021", "regular", 18), "2021", 14),
+            14
+        );
+        assert_eq!(weeks_played(&nfl_state("2021", "post", 1), "2021", 14), 14);
+        assert_eq!(weeks_played(&nfl_state("2021", "pre", 0), "2021", 14), 0);
+        // a past season is all played, whatever the NFL is doing now
+        assert_eq!(weeks_played(&nfl_state("2022", "pre", 0), "2021", 14), 14);
+        assert_eq!(
+            weeks_played(&nfl_state("2022", "regular", 2), "2021", 14),
+            14
+        );
+        // a league for next season hasn't started
+        assert_eq!(weeks_played(&nfl_state("2021", "post", 1), "2022", 14), 0);
+    }
+
+    #[test]
+    fn played_matchups_stop_at_the_current_week() {
+        let weeks: Vec<Vec<SleeperMatchup>> = serde_json::from_value(serde_json::json!([
+            [
+                {"roster_id": 1, "matchup_id": 1, "points": 110.2, "starters": ["4034"],
+                 "players": ["4034", "6794"], "players_points": {"4034": 30.1, "6794": 12.0}},
+                {"roster_id": 2, "matchup_id": 1, "points": 98.6},
+                {"roster_id": 3, "matchup_id": 2, "points": 120.0},
+                {"roster_id": 4, "matchup_id": 2, "points": 87.4}
+            ],
+            [
+                {"roster_id": 1, "matchup_id": 2, "points": 101.0},
+                {"roster_id": 3, "matchup_id": 1, "points": 99.9},
+                {"roster_id": 2, "matchup_id": 1, "points": 105.3},
+                {"roster_id": 4, "matchup_id": 2, "points": 76.1}
+            ],
+            [
+                {"roster_id": 1, "matchup_id": 1, "points": 12.5},
+                {"roster_id": 4, "matchup_id": 1, "points": 0.0},
+                {"roster_id": 2, "matchup_id": 2, "points": 8.0},
+                {"roster_id": 3, "matchup_id": 2, "points": 3.2},
+                {"roster_id": 5, "matchup_id": null, "points": 0.0}
+            ]
+        ]))
+        .unwrap();
+        let played_through = weeks_played(&nfl_state("2021", "regular", 3), "2021", 3);
+        let team = |r: u32| Some(FantasyTeam::test(&r.to_string()));
+        let schedule = Schedule {
+            weeks: weeks
+                .iter()
+                .enumerate()
+                .map(|(i, week)| schedule_week(i as u32 + 1, week, played_through, team))
+                .collect(),
+        };
+
+        let played = schedule.played_matchups();
+        let games: Vec<(u32, &str, &str)> = played
+            .iter()
+            .map(|m| (m.week_num, m.team1.id.as_str(), m.team2.id.as_str()))
+            .collect();
+        assert_eq!(
+            games,
+            [(1, "1", "2"), (1, "3", "4"), (2, "3", "2"), (2, "1", "4")]
+        );
+        let remaining = schedule.remaining_matchups();
+        assert_eq!(remaining.len(), 2);
+        assert!(remaining.iter().all(|m| m.week_num == 3));
+    }
+
+    fn league(settings: serde_json::Value) -> SleeperLeague {
+        serde_json::from_value(serde_json::json!({
+            "season": "2021",
+            "status": "in_season",
+            "total_rosters": 12,
+            "scoring_settings": {"rec": 0.5},
+            "roster_positions": ["QB", "RB", "BN"],
+            "settings": settings,
+            "previous_league_id": "0",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn league_settings_from_sleeper() {
+        let settings = league_settings(league(serde_json::json!({
+            "waiver_type": 2,
+            "waiver_budget": 100,
+            "trade_deadline": 11,
+            "playoff_teams": 6,
+            "playoff_week_start": 15,
+        })));
+        assert_eq!(settings.status, LeagueStatus::InSeason);
+        assert_eq!(settings.waiver_type, WaiverType::Faab);
+        assert_eq!(settings.faab_budget, Some(100));
+        assert_eq!(settings.trade_deadline, Some(TradeDeadline::Week(11)));
+        assert_eq!(settings.previous_league_id, None);
+        assert_eq!(
+            settings.playoff_round_weeks,
+            vec![vec![15], vec![16], vec![17]]
+        );
+        assert_eq!(settings.last_week(), 17);
+
+        // a budget only counts for FAAB, and Sleeper's defaults fill in what's missing
+        let settings = league_settings(league(serde_json::json!({
+            "waiver_type": 0,
+            "waiver_budget": 100,
+            "trade_deadline": 99,
+        })));
+        assert_eq!(settings.waiver_type, WaiverType::Rolling);
+        assert_eq!(settings.faab_budget, None);
+        assert_eq!(settings.trade_deadline, None);
+        assert_eq!(settings.playoff_teams, DEFAULT_PLAYOFF_TEAMS);
+        assert_eq!(settings.playoff_week_start, DEFAULT_PLAYOFF_WEEK_START);
+    }
+
+    #[test]
+    fn playoff_rounds_by_round_type() {
+        let weeks = |round_type: u32, rounds: u32| {
+            let settings: SleeperLeagueSettings = serde_json::from_value(serde_json::json!({
+                "playoff_week_start": 14,
+                "playoff_round_type": round_type,
+            }))
+            .unwrap();
+            playoff_round_weeks(&settings, rounds)
         };
-        Ok(matchups)
+        assert_eq!(weeks(0, 3), vec![vec![14], vec![15], vec![16]]);
+        assert_eq!(weeks(1, 3), vec![vec![14], vec![15], vec![16, 17]]);
+        assert_eq!(weeks(2, 2), vec![vec![14, 15], vec![16, 17]]);
     }
 }
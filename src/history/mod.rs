@@ -0,0 +1,167 @@
+use super::analytics::Record;
+use super::fantasy_client::{FantasyClient, FantasyTeam, PastMatchup, PastTeam};
+use super::players;
+use super::storage::{league_key, Storage};
+use std::error::Error;
+
+// Every finished game the league has ever played, oldest season first.  Seasons that are over
+// are stored so they're only fetched once.
+pub async fn load(
+    client: &dyn FantasyClient,
+    storage: &Storage,
+    league_id: &str,
+) -> Result<Vec<PastMatchup>, Box<dyn Error>> {
+    let seasons = client.get_seasons().await?;
+    let mut history = vec![];
+    for season in seasons.iter().rev() {
+        let key = league_key(league_id, &format!("history/seasons/{}", season.season));
+        if let Some(matchups) = storage.load::<Vec<PastMatchup>>(&key) {
+            history.extend(matchups);
+            continue;
+        }
+        let results = client.get_season_results(season).await?;
+        if results.complete {
+            storage.save(&key, &results.matchups)?;
+        }
+        history.extend(results.matchups);
+    }
+    Ok(history)
+}
+
+// Fetches whatever's new and stores the whole history, this season included, for `stored`.
+pub async fn update(
+    client: &dyn FantasyClient,
+    storage: &Storage,
+    league_id: &str,
+) -> Result<Vec<PastMatchup>, Box<dyn Error>> {
+    let history = load(client, storage, league_id).await?;
+    storage.save(&league_key(league_id, "history/all"), &history)?;
+    Ok(history)
+}
+
+// The history as of the last `update`, without fetching anything.
+pub fn stored(storage: &Storage, league_id: &str) -> Option<Vec<PastMatchup>> {
+    storage.load(&league_key(league_id, "history/all"))
+}
+
+// The owner whose name, or any of whose team names, best matches what somebody typed, as they
+// were most recently.
+pub fn find_owner(history: &[PastMatchup], query: &str) -> Option<PastTeam> {
+    let teams: Vec<&PastTeam> = history.iter().flat_map(|m| [&m.team1, &m.team2]).collect();
+    let owner_id = players::best_match(
+        query,
+        teams.iter().rev().enumerate().flat_map(|(i, t)| {
+            [
+                (t.owner_name.as_str(), i as u64, &t.owner_id),
+                (t.team_name.as_str(), i as u64, &t.owner_id),
+            ]
+        }),
+    )?;
+    teams
+        .into_iter()
+        .rev()
+        .find(|t| &t.owner_id == owner_id)
+        .cloned()
+}
+
+// The owner of one of this season's teams, as they were most recently.  Teams go by their owner's
+// ID on some platforms, so either that or the owner's name will do.
+pub fn owner_of(history: &[PastMatchup], team: &FantasyTeam) -> Option<PastTeam> {
+    history
+        .iter()
+        .rev()
+        .flat_map(|m| [&m.team1, &m.team2])
+        .find(|t| t.owner_id == team.id || t.owner_name == team.owner_name)
+        .cloned()
+}
+
+// Everything two owners have done against each other, from the first one's side.
+pub struct HeadToHead {
+    pub owner1: PastTeam,
+    pub owner2: PastTeam,
+    pub record: Record,
+    pub points1: f64,
+    pub points2: f64,
+    // with owner1 as team1
+    pub biggest_win1: Option<PastMatchup>,
+    pub biggest_win2: Option<PastMatchup>,
+    pub playoff_meetings: Vec<PastMatchup>,
+}
+
+pub fn head_to_head(history: &[PastMatchup], owner1: &PastTeam, owner2: &PastTeam) -> HeadToHead {
+    let mut h2h = HeadToHead {
+        owner1: owner1.clone(),
+        owner2: owner2.clone(),
+        record: Record::default(),
+        points1: 0.0,
+        points2: 0.0,
+        biggest_win1: None,
+        biggest_win2: None,
+        playoff_meetings: vec![],
+    };
+    let margin = |m: &Option<PastMatchup>| m.as_ref().map_or(0.0, |m| (m.score1 - m.score2).abs());
+    for m in history.iter() {
+        // turn it around so owner1 is always team1
+        let m = if m.team1.owner_id == owner1.owner_id && m.team2.owner_id == owner2.owner_id {
+            m.clone()
+        } else if m.team1.owner_id == owner2.owner_id && m.team2.owner_id == owner1.owner_id {
+            PastMatchup {
+                team1: m.team2.clone(),
+                team2: m.team1.clone(),
+                score1: m.score2,
+                score2: m.score1,
+                ..m.clone()
+            }
+        } else {
+            continue;
+        };
+        h2h.record.add(m.score1, m.score2);
+        h2h.points1 += m.score1;
+        h2h.points2 += m.score2;
+        let m_margin = (m.score1 - m.score2).abs();
+        if m.score1 > m.score2 && m_margin > margin(&h2h.biggest_win1) {
+            h2h.biggest_win1 = Some(m.clone());
+        } else if m.score2 > m.score1 && m_margin > margin(&h2h.biggest_win2) {
+            h2h.biggest_win2 = Some(m.clone());
+        }
+        if m.playoff {
+            h2h.playoff_meetings.push(m);
+        }
+    }
+    h2h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn head_to_head_across_renamed_teams() {
+        let alice_old = PastTeam::test("Alice", "Gronk Squad");
+        let alice = PastTeam::test("Alice", "Mahomies");
+        let bob = PastTeam::test("Bob", "Bob's Burgers");
+        let carol = PastTeam::test("Carol", "Carolina");
+        let history = vec![
+            PastMatchup::test("2020", 1, &alice_old, &bob, 120.0, 80.0),
+            PastMatchup::test("2020", 1, &alice_old, &carol, 100.0, 90.0),
+            PastMatchup::test("2021", 1, &bob, &alice, 110.0, 100.0),
+            PastMatchup {
+                playoff: true,
+                ..PastMatchup::test("2021", 1, &alice, &bob, 130.0, 125.0)
+            },
+        ];
+
+        // old team names still find the owner, who shows up as they are now
+        let found = find_owner(&history, "gronk squad").unwrap();
+        assert_eq!(found.team_name, "Mahomies");
+        let bob = find_owner(&history, "bob").unwrap();
+
+        let h2h = head_to_head(&history, &found, &bob);
+        assert_eq!(h2h.record.to_string(), "2-1");
+        assert_eq!(h2h.points1, 350.0);
+        assert_eq!(h2h.points2, 315.0);
+        assert_eq!(h2h.biggest_win1.unwrap().score1, 120.0);
+        assert_eq!(h2h.biggest_win2.unwrap().score2, 110.0);
+        assert_eq!(h2h.playoff_meetings.len(), 1);
+    }
+}
@@ -0,0 +1,282 @@
+use super::draft::{self, PickValue};
+use super::fantasy_client::{
+    Draft, FantasyPlayer, FantasyRoster, FantasyTeam, KeeperRules, TradedPick, EMPTY_SLOT_ID,
+};
+use std::collections::HashMap;
+
+pub struct Keeper {
+    pub player: FantasyPlayer,
+    // None if nobody drafted them
+    pub drafted_round: Option<u32>,
+    // the round of the pick it takes to keep them
+    pub cost: u32,
+    pub points: f64,
+    // points over what a pick in the cost round got at the same position
+    pub value: f64,
+    // one of the team's best keepers, up to the most they can keep, each priced with a pick of
+    // its own
+    pub suggested: bool,
+}
+
+pub struct TeamKeepers {
+    pub team: FantasyTeam,
+    // suggested keepers first, then best value first
+    pub keepers: Vec<Keeper>,
+    // next season's picks that have changed hands
+    pub traded_away: Vec<TradedPick>,
+    pub acquired: Vec<TradedPick>,
+}
+
+// The rounds a team has a pick in for a season, counting picks it's traded for.
+fn owned_rounds(team: &FantasyTeam, season: &str, rounds: u32, traded: &[TradedPick]) -> Vec<u32> {
+    let traded: Vec<&TradedPick> = traded.iter().filter(|p| p.season == season).collect();
+    let mut owned: Vec<u32> = (1..=rounds)
+        .filter(|r| {
+            !traded
+                .iter()
+                .any(|p| p.original_team.id == team.id && p.round == *r)
+        })
+        .collect();
+    owned.extend(
+        traded
+            .iter()
+            .filter(|p| p.owner.id == team.id)
+            .map(|p| p.round),
+    );
+    owned
+}
+
+// What a pick at a position in a round got this season: the expected season of the first player
+// at the position taken in that round or later, the way draft grades see it.
+fn round_value(values: &[PickValue], position: &str, round: u32) -> f64 {
+    values
+        .iter()
+        .filter(|v| v.pick.player.position == position && v.pick.round >= round)
+        .min_by_key(|v| v.pick.pick_no)
+        .map_or(0.0, |v| v.expected)
+}
+
+// Picks the team's best keepers, best value first, giving each one the latest pick it can have
+// that a better keeper hasn't already taken.  `limits` is the latest round each can cost.
+fn suggest(
+    keepers: &mut [Keeper],
+    limits: &[u32],
+    owned: &[u32],
+    max_keepers: u32,
+    values: &[PickValue],
+) {
+    let mut left = owned.to_vec();
+    let mut suggested = 0;
+    for (keeper, limit) in keepers.iter_mut().zip(limits.iter()) {
+        if suggested == max_keepers {
+            break;
+        }
+        let pick = left
+            .iter()
+            .enumerate()
+            .filter(|(_, r)| *r <= limit)
+            .max_by_key(|(_, r)| **r)
+            .map(|(i, _)| i);
+        if let Some(i) = pick {
+            keeper.cost = left.remove(i);
+            keeper.value =
+                keeper.points - round_value(values, &keeper.player.position, keeper.cost);
+            keeper.suggested = true;
+            suggested += 1;
+        }
+    }
+}
+
+// Each team's keeper options for next season, priced by this season's draft and scoring.
+pub fn eligible(
+    rules: &KeeperRules,
+    draft: &Draft,
+    rosters: &[FantasyRoster],
+    season_points: &HashMap<String, f64>,
+    traded: &[TradedPick],
+    next_season: &str,
+) -> Vec<TeamKeepers> {
+    let values = draft::grade(&draft.picks, season_points).picks;
+    let for_team = |team: &FantasyTeam, owner: bool| {
+        let mut picks: Vec<TradedPick> = traded
+            .iter()
+            .filter(|p| {
+                p.season == next_season
+                    && if owner {
+                        p.owner.id == team.id
+                    } else {
+                        p.original_team.id == team.id
+                    }
+            })
+            .cloned()
+            .collect();
+        picks.sort_by_key(|p| p.round);
+        picks
+    };
+
+    rosters
+        .iter()
+        .map(|roster| {
+            let owned = owned_rounds(&roster.team, next_season, draft.rounds, traded);
+            let mut keepers: Vec<(Keeper, u32)> = roster
+                .players()
+                .filter(|p| p.id != EMPTY_SLOT_ID)
+                .filter_map(|player| {
+                    let drafted_round = draft
+                        .picks
+                        .iter()
+                        .find(|p| p.player.id == player.id)
+                        .map(|p| p.round);
+                    // escalating past the 1st round means they can't be kept
+                    let round = match drafted_round {
+                        Some(r) => r.checked_sub(rules.round_escalation).filter(|r| *r > 0)?,
+                        None => rules.undrafted_round.unwrap_or(draft.rounds),
+                    };
+                    // without that pick, it takes the next one up the team still has
+                    let cost = owned.iter().copied().filter(|r| *r <= round).max()?;
+                    let points = season_points.get(&player.id).copied().unwrap_or(0.0);
+                    let keeper = Keeper {
+                        player: player.clone(),
+                        drafted_round,
+                        cost,
+                        points,
+                        value: points - round_value(&values, &player.position, cost),
+                        suggested: false,
+                    };
+                    Some((keeper, round))
+                })
+                .collect();
+            keepers.sort_by(|a, b| b.0.value.partial_cmp(&a.0.value).unwrap());
+            let (mut keepers, limits): (Vec<Keeper>, Vec<u32>) = keepers.into_iter().unzip();
+            suggest(&mut keepers, &limits, &owned, rules.max_keepers, &values);
+            keepers.sort_by(|a, b| {
+                b.suggested
+                    .cmp(&a.suggested)
+                    .then(b.value.partial_cmp(&a.value).unwrap())
+            });
+            TeamKeepers {
+                team: roster.team.clone(),
+                keepers,
+                traded_away: for_team(&roster.team, false),
+                acquired: for_team(&roster.team, true),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::{DraftPick, DraftStatus};
+
+    fn player(id: &str) -> FantasyPlayer {
+        FantasyPlayer {
+            id: id.to_string(),
+            name: id.to_string(),
+            position: "RB".to_string(),
+            nfl_team: None,
+            injury_status: None,
+        }
+    }
+
+    fn pick(pick_no: u32, round: u32, team_id: &str, player_id: &str) -> DraftPick {
+        DraftPick {
+            pick_no,
+            round,
+            team: FantasyTeam::test(team_id),
+            player: player(player_id),
+            keeper: false,
+        }
+    }
+
+    #[test]
+    fn costs_escalate_and_follow_traded_picks() {
+        let draft = Draft {
+            id: "1".to_string(),
+            status: DraftStatus::Complete,
+            rounds: 3,
+            picks: vec![
+                pick(1, 1, "A", "rb1"),
+                pick(2, 1, "B", "rb2"),
+                pick(3, 2, "B", "rb3"),
+                pick(4, 2, "A", "rb4"),
+                pick(5, 3, "A", "rb5"),
+                pick(6, 3, "B", "rb6"),
+            ],
+        };
+        let rosters = vec![FantasyRoster {
+            team: FantasyTeam::test("A"),
+            starters: vec![player("rb1"), FantasyPlayer::empty_slot("RB")],
+            bench: vec![player("rb4")],
+            reserve: vec![player("rb5")],
+            taxi: vec![player("rookie")],
+        }];
+        let points: HashMap<String, f64> = [
+            ("rb1", 250.0),
+            ("rb2", 200.0),
+            ("rb3", 120.0),
+            ("rb4", 180.0),
+            ("rb5", 90.0),
+            ("rb6", 60.0),
+            ("rookie", 100.0),
+        ]
+        .iter()
+        .map(|(id, p)| (id.to_string(), *p))
+        .collect();
+        // A's next 2nd-rounder belongs to B now
+        let traded = vec![TradedPick {
+            season: "2022".to_string(),
+            round: 2,
+            original_team: FantasyTeam::test("A"),
+            owner: FantasyTeam::test("B"),
+        }];
+        let rules = KeeperRules {
+            max_keepers: 2,
+            round_escalation: 1,
+            undrafted_round: None,
+        };
+        let teams = eligible(&rules, &draft, &rosters, &points, &traded, "2022");
+        let a = &teams[0];
+        assert_eq!(a.traded_away.len(), 1);
+        assert!(a.acquired.is_empty());
+
+        let cost = |id: &str| a.keepers.iter().find(|k| k.player.id == id).map(|k| k.cost);
+        // a 1st-rounder can't move up, and a 3rd-rounder takes the 1st since the 2nd is gone
+        assert_eq!(cost("rb1"), None);
+        assert_eq!(cost("rb4"), Some(1));
+        assert_eq!(cost("rb5"), Some(1));
+        assert_eq!(cost("rookie"), Some(3));
+
+        // rb4 is compared with the best RB season a 1st-rounder had
+        let rb4 = a.keepers.iter().find(|k| k.player.id == "rb4").unwrap();
+        assert_eq!(rb4.value, 180.0 - 250.0);
+        assert_eq!(a.keepers[0].player.id, "rookie");
+        assert_eq!(a.keepers[0].value, 100.0 - 90.0);
+
+        // rb4 and rb5 can't both have A's only 1st; the better one gets it
+        let suggested = |teams: &[TeamKeepers]| -> Vec<(String, u32)> {
+            teams[0]
+                .keepers
+                .iter()
+                .filter(|k| k.suggested)
+                .map(|k| (k.player.id.clone(), k.cost))
+                .collect()
+        };
+        assert_eq!(
+            suggested(&teams),
+            [("rookie".to_string(), 3), ("rb4".to_string(), 1)]
+        );
+        let rules = KeeperRules {
+            max_keepers: 3,
+            ..rules
+        };
+        let teams = eligible(&rules, &draft, &rosters, &points, &traded, "2022");
+        assert_eq!(suggested(&teams).len(), 2);
+        let rules = KeeperRules {
+            max_keepers: 1,
+            ..rules
+        };
+        let teams = eligible(&rules, &draft, &rosters, &points, &traded, "2022");
+        assert_eq!(suggested(&teams), [("rookie".to_string(), 3)]);
+    }
+}
@@ -1,5 +1,21 @@
+pub mod analytics;
 pub mod discord_client;
+pub mod draft;
 pub mod fantasy_client;
+pub mod history;
+pub mod keepers;
+pub mod lineup;
+pub mod nfl;
+pub mod players;
+pub mod playoffs;
+pub mod preview;
+pub mod recap;
+pub mod records;
+pub mod render;
+pub mod startsit;
+pub mod storage;
+pub mod trending;
+pub mod waivers;
 
 #[cfg(test)]
 mod tests {}
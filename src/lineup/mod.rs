@@ -0,0 +1,118 @@
+use super::fantasy_client::{FantasyPlayer, FantasyRoster};
+use std::collections::HashMap;
+
+// Statuses that mean a player isn't going to score anything this week.  Sleeper and ESPN
+// spell them differently.
+pub fn is_out(player: &FantasyPlayer) -> bool {
+    matches!(
+        player.injury_status.as_deref(),
+        Some("Out")
+            | Some("OUT")
+            | Some("IR")
+            | Some("INJURY_RESERVE")
+            | Some("Sus")
+            | Some("SUSPENSION")
+            | Some("PUP")
+            | Some("COV")
+    )
+}
+
+pub enum LineupIssue {
+    OnBye(FantasyPlayer),
+    Out(FantasyPlayer),
+    EmptySlot(String),
+}
+
+impl LineupIssue {
+    pub fn describe(&self) -> String {
+        match self {
+            LineupIssue::OnBye(p) => format!("{} is on bye", p.name),
+            LineupIssue::Out(p) => format!(
+                "{} is ruled {}",
+                p.name,
+                p.injury_status.as_deref().unwrap_or("out")
+            ),
+            LineupIssue::EmptySlot(position) if position.is_empty() => {
+                "a starting slot is empty".to_string()
+            }
+            LineupIssue::EmptySlot(position) => format!("the {} slot is empty", position),
+        }
+    }
+}
+
+// Starters who won't play this week.  `byes` maps NFL team to bye week.
+pub fn check(
+    roster: &FantasyRoster,
+    week_num: u32,
+    byes: &HashMap<String, u32>,
+) -> Vec<LineupIssue> {
+    roster
+        .starters
+        .iter()
+        .filter_map(|p| {
+            if p.is_empty_slot() {
+                Some(LineupIssue::EmptySlot(p.position.clone()))
+            } else if p
+                .nfl_team
+                .as_ref()
+                .and_then(|t| byes.get(t))
+                .is_some_and(|bye| *bye == week_num)
+            {
+                Some(LineupIssue::OnBye(p.clone()))
+            } else if is_out(p) {
+                Some(LineupIssue::Out(p.clone()))
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::FantasyTeam;
+
+    fn player(name: &str, team: &str, status: Option<&str>) -> FantasyPlayer {
+        FantasyPlayer {
+            id: name.to_string(),
+            name: name.to_string(),
+            position: "WR".to_string(),
+            nfl_team: Some(team.to_string()),
+            injury_status: status.map(|s| s.to_string()),
+        }
+    }
+
+    #[test]
+    fn finds_byes_outs_and_empty_slots() {
+        let roster = FantasyRoster {
+            team: FantasyTeam::test("1"),
+            starters: vec![
+                player("Fine", "KC", None),
+                player("Resting", "DET", None),
+                player("Hurt", "BUF", Some("Out")),
+                player("Iffy", "BUF", Some("Questionable")),
+                FantasyPlayer::empty_slot("FLEX"),
+            ],
+            bench: vec![player("Benched", "DET", Some("IR"))],
+            reserve: vec![],
+            taxi: vec![],
+        };
+        let byes: HashMap<String, u32> = vec![("DET".to_string(), 9), ("KC".to_string(), 10)]
+            .into_iter()
+            .collect();
+
+        let issues: Vec<String> = check(&roster, 9, &byes)
+            .iter()
+            .map(|i| i.describe())
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                "Resting is on bye",
+                "Hurt is ruled Out",
+                "the FLEX slot is empty"
+            ]
+        );
+    }
+}
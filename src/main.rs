@@ -4,6 +4,7 @@ use football_rustbot::fantasy_client::sleeper;
 use football_rustbot::fantasy_client::{FflClient, FflClientType, LeagueConfig, LeagueType};
 use serde::Deserialize;
 use std::fs::File;
+use std::sync::Arc;
 
 const CONFIG_FILE: &str = "config.json";
 
@@ -41,6 +42,12 @@ async fn main() {
 
     let config = load_config();
 
+    let sleeper_players = Arc::new(
+        sleeper::SleeperPlayers::load()
+            .await
+            .expect("Could not initialize players list"),
+    );
+
     let mut ffl_clients: Vec<FflClient> = vec![];
     for league_config in config.leagues {
         let league_id = league_config.league_id.clone();
@@ -56,7 +63,9 @@ async fn main() {
             },
             LeagueType::SLEEPER => FflClient {
                 config: league_config,
-                client_type: FflClientType::SLEEPER(sleeper::SleeperClient::new(league_id).await),
+                client_type: FflClientType::SLEEPER(
+                    sleeper::SleeperClient::new(league_id, sleeper_players.clone()).await,
+                ),
             },
         };
         ffl_clients.push(ffl_client);
@@ -74,6 +83,7 @@ async fn main() {
         ffl_clients,
         config.discord_config.covid_json_url,
         config.discord_config.power_ranking_url_format,
+        sleeper_players,
     )
     .await;
     client.start().await.expect("client error");
@@ -0,0 +1,297 @@
+use super::storage::Storage;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use log::debug;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::error::Error;
+
+// ESPN's public scoreboard doesn't need any credentials, unlike the fantasy API
+const SCOREBOARD_URL: &str =
+    "https://site.api.espn.com/apis/site/v2/sports/football/nfl/scoreboard";
+const REGULAR_SEASON_TYPE: u32 = 2;
+pub const REGULAR_SEASON_WEEKS: u32 = 18;
+const SECS_PER_QUARTER: u32 = 15 * 60;
+const SECS_PER_GAME: u32 = 4 * SECS_PER_QUARTER;
+
+// Sleeper's abbreviations for every NFL team
+pub const NFL_TEAMS: [&str; 32] = [
+    "ARI", "ATL", "BAL", "BUF", "CAR", "CHI", "CIN", "CLE", "DAL", "DEN", "DET", "GB", "HOU",
+    "IND", "JAX", "KC", "LAC", "LAR", "LV", "MIA", "MIN", "NE", "NO", "NYG", "NYJ", "PHI", "PIT",
+    "SEA", "SF", "TB", "TEN", "WAS",
+];
+
+#[derive(Deserialize, Debug)]
+struct ScoreboardResponse {
+    events: Vec<ScoreboardEvent>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ScoreboardEvent {
+    id: String,
+    date: String,
+    competitions: Vec<ScoreboardCompetition>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ScoreboardCompetition {
+    competitors: Vec<ScoreboardCompetitor>,
+    status: ScoreboardStatus,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ScoreboardCompetitor {
+    home_away: String,
+    team: ScoreboardTeam,
+    score: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ScoreboardTeam {
+    abbreviation: String,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ScoreboardStatus {
+    clock: Option<f64>,
+    display_clock: Option<String>,
+    period: Option<u32>,
+    #[serde(rename = "type")]
+    status_type: ScoreboardStatusType,
+}
+
+#[derive(Deserialize, Debug)]
+struct ScoreboardStatusType {
+    state: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GameStatus {
+    Scheduled,
+    InProgress,
+    Final,
+}
+
+#[derive(Clone, Debug)]
+pub struct NflGame {
+    pub id: String,
+    pub home_team: String,
+    pub away_team: String,
+    pub kickoff: DateTime<Utc>,
+    pub status: GameStatus,
+    pub quarter: Option<u32>,
+    // seconds left in the current quarter
+    pub clock_secs: Option<u32>,
+    pub display_clock: Option<String>,
+    pub home_score: Option<u32>,
+    pub away_score: Option<u32>,
+}
+
+impl NflGame {
+    pub fn involves(&self, team: &str) -> bool {
+        self.home_team == team || self.away_team == team
+    }
+
+    pub fn is_locked(&self, now: DateTime<Utc>) -> bool {
+        self.status != GameStatus::Scheduled || self.kickoff <= now
+    }
+
+    // How much of the game is left to play, from 1.0 before kickoff to 0.0 when it's over.
+    pub fn fraction_remaining(&self) -> f64 {
+        match self.status {
+            GameStatus::Scheduled => 1.0,
+            GameStatus::Final => 0.0,
+            GameStatus::InProgress => {
+                // overtime counts as the fifth quarter, with nothing after it
+                let quarters_left = 4u32.saturating_sub(self.quarter.unwrap_or(1));
+                let secs_left = quarters_left * SECS_PER_QUARTER + self.clock_secs.unwrap_or(0);
+                (secs_left as f64 / SECS_PER_GAME as f64).min(1.0)
+            }
+        }
+    }
+}
+
+// ESPN and Sleeper disagree on a couple of team abbreviations, so use Sleeper's everywhere.
+pub fn normalize_team(abbreviation: &str) -> String {
+    match abbreviation {
+        "WSH" => "WAS".to_string(),
+        "JAC" => "JAX".to_string(),
+        a => a.to_string(),
+    }
+}
+
+// ESPN leaves the seconds off of its dates, e.g. "2021-09-10T00:20Z".
+fn parse_date(date: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(date)
+        .map(|d| d.with_timezone(&Utc))
+        .ok()
+        .or_else(|| {
+            NaiveDateTime::parse_from_str(date, "%Y-%m-%dT%H:%MZ")
+                .ok()
+                .map(|d| DateTime::from_naive_utc_and_offset(d, Utc))
+        })
+}
+
+pub async fn get_games(season: &str, week: u32) -> Result<Vec<NflGame>, Box<dyn Error>> {
+    let url = format!(
+        "{}?seasontype={}&week={}&dates={}",
+        SCOREBOARD_URL, REGULAR_SEASON_TYPE, week, season
+    );
+    let resp = reqwest::get(url)
+        .await?
+        .json::<ScoreboardResponse>()
+        .await?;
+    debug!("NFL scoreboard for week {}: {:?}", week, resp);
+
+    let mut games: Vec<NflGame> = vec![];
+    for event in resp.events {
+        let kickoff = match parse_date(&event.date) {
+            Some(k) => k,
+            None => continue,
+        };
+        let competition = match event.competitions.first() {
+            Some(c) => c,
+            None => continue,
+        };
+        let competitor = |home_away: &str| {
+            competition
+                .competitors
+                .iter()
+                .find(|c| c.home_away == home_away)
+        };
+        let (home, away) = match (competitor("home"), competitor("away")) {
+            (Some(h), Some(a)) => (h, a),
+            _ => continue,
+        };
+        let status = match competition.status.status_type.state.as_str() {
+            "in" => GameStatus::InProgress,
+            "post" => GameStatus::Final,
+            _ => GameStatus::Scheduled,
+        };
+        let score = |c: &ScoreboardCompetitor| match status {
+            GameStatus::Scheduled => None,
+            _ => c.score.as_ref().and_then(|s| s.parse::<u32>().ok()),
+        };
+        let in_progress = status == GameStatus::InProgress;
+        games.push(NflGame {
+            id: event.id,
+            home_team: normalize_team(&home.team.abbreviation),
+            away_team: normalize_team(&away.team.abbreviation),
+            kickoff,
+            status,
+            quarter: competition.status.period.filter(|_| in_progress),
+            clock_secs: competition
+                .status
+                .clock
+                .filter(|_| in_progress)
+                .map(|c| c as u32),
+            display_clock: competition
+                .status
+                .display_clock
+                .clone()
+                .filter(|_| in_progress),
+            home_score: score(home),
+            away_score: score(away),
+        });
+    }
+    games.sort_by_key(|g| g.kickoff);
+    Ok(games)
+}
+
+pub fn first_kickoff(games: &[NflGame]) -> Option<DateTime<Utc>> {
+    games.iter().map(|g| g.kickoff).min()
+}
+
+pub fn game_for_team<'a>(games: &'a [NflGame], team: &str) -> Option<&'a NflGame> {
+    games.iter().find(|g| g.involves(team))
+}
+
+// Teams without a game in the given week's schedule.
+pub fn teams_on_bye(games: &[NflGame]) -> Vec<&'static str> {
+    NFL_TEAMS
+        .iter()
+        .filter(|t| game_for_team(games, t).is_none())
+        .copied()
+        .collect()
+}
+
+// Each team's bye week for the season, keyed by team.  The schedule doesn't change once it's
+// out, so this only hits ESPN the first time for each season.
+pub async fn get_bye_weeks(
+    storage: &Storage,
+    season: &str,
+) -> Result<HashMap<String, u32>, Box<dyn Error>> {
+    let key = format!("nfl/byes/{}", season);
+    if let Some(byes) = storage.load(&key) {
+        return Ok(byes);
+    }
+    let mut byes: HashMap<String, u32> = HashMap::new();
+    for week in 1..=REGULAR_SEASON_WEEKS {
+        let games = get_games(season, week).await?;
+        // an empty week means the schedule isn't out yet, not that the whole league is off
+        if games.is_empty() {
+            continue;
+        }
+        for team in teams_on_bye(&games) {
+            byes.insert(team.to_string(), week);
+        }
+    }
+    if byes.len() == NFL_TEAMS.len() {
+        storage.save(&key, &byes)?;
+    }
+    Ok(byes)
+}
+
+// Whether a player can no longer be moved in or out of a lineup because their game has started.
+pub fn is_locked(games: &[NflGame], team: Option<&str>, now: DateTime<Utc>) -> bool {
+    team.and_then(|t| game_for_team(games, t))
+        .is_some_and(|g| g.is_locked(now))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn game(status: GameStatus, quarter: Option<u32>, clock_secs: Option<u32>) -> NflGame {
+        NflGame {
+            id: "1".to_string(),
+            home_team: "KC".to_string(),
+            away_team: "BUF".to_string(),
+            kickoff: Utc::now(),
+            status,
+            quarter,
+            clock_secs,
+            display_clock: None,
+            home_score: None,
+            away_score: None,
+        }
+    }
+
+    #[test]
+    fn fraction_remaining_by_status() {
+        assert_eq!(
+            game(GameStatus::Scheduled, None, None).fraction_remaining(),
+            1.0
+        );
+        assert_eq!(
+            game(GameStatus::Final, None, None).fraction_remaining(),
+            0.0
+        );
+        let halftime = game(GameStatus::InProgress, Some(2), Some(0));
+        assert_eq!(halftime.fraction_remaining(), 0.5);
+        let overtime = game(GameStatus::InProgress, Some(5), Some(300));
+        assert!(overtime.fraction_remaining() < 0.1);
+    }
+
+    #[test]
+    fn byes_and_remaining_time() {
+        let games = vec![game(GameStatus::InProgress, Some(3), Some(450))];
+        let byes = teams_on_bye(&games);
+        assert_eq!(byes.len(), 30);
+        assert!(!byes.contains(&"KC"));
+        assert_eq!(games[0].fraction_remaining(), 0.375);
+        assert!(is_locked(&games, Some("KC"), Utc::now()));
+        assert!(!is_locked(&games, Some("DET"), Utc::now()));
+    }
+}
@@ -0,0 +1,197 @@
+use super::fantasy_client::{FantasyRoster, FantasyTeam, PlayerProjection};
+
+// Positions that show up in fantasy lineups, in Sleeper's spelling.
+pub const FANTASY_POSITIONS: [&str; 6] = ["QB", "RB", "WR", "TE", "K", "DEF"];
+
+// How a player looks from one league: who has them, and how they score under its rules.
+pub struct LeaguePlayer {
+    pub league_name: String,
+    pub rostered_by: Option<FantasyTeam>,
+    pub projection: Option<PlayerProjection>,
+}
+
+// dropped from names since people never type them
+const SUFFIXES: [&str; 6] = ["jr", "sr", "ii", "iii", "iv", "v"];
+
+// Common short forms of first names, so "mike" finds Michael and "bob" finds Robert.
+fn full_first_name(name: &str) -> &str {
+    match name {
+        "mike" | "mikey" => "michael",
+        "bob" | "bobby" | "rob" | "robbie" => "robert",
+        "bill" | "billy" | "will" | "willie" => "william",
+        "tony" => "anthony",
+        "jim" | "jimmy" => "james",
+        "joe" | "joey" => "joseph",
+        "tom" | "tommy" => "thomas",
+        "dan" | "danny" => "daniel",
+        "dave" => "david",
+        "steve" => "steven",
+        "chris" => "christopher",
+        "matt" => "matthew",
+        "nick" => "nicholas",
+        "ben" => "benjamin",
+        "sam" => "samuel",
+        "gabe" => "gabriel",
+        "zach" | "zack" => "zachary",
+        "alex" => "alexander",
+        "jon" | "johnny" => "jonathan",
+        "josh" => "joshua",
+        "mitch" => "mitchell",
+        "ken" | "kenny" => "kenneth",
+        "drew" => "andrew",
+        "pat" => "patrick",
+        n => n,
+    }
+}
+
+// Lowercase letters and digits only, without suffixes and with nicknames spelled out, so
+// "D.K. Metcalf", "dk metcalf" and "DK Metcalf Jr." all look the same.
+pub fn normalize_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|c| if c == '-' { ' ' } else { c })
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .flat_map(|c| c.to_lowercase())
+        .collect();
+    name.split_whitespace()
+        .filter(|w| !SUFFIXES.contains(w))
+        .map(full_first_name)
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+// How far a player's name is from what somebody typed, lower being closer, or None if it's
+// not close at all.
+pub fn match_distance(query: &str, name: &str) -> Option<usize> {
+    let query = normalize_name(query);
+    let name = normalize_name(name);
+    if query.is_empty() {
+        return None;
+    }
+    if query == name {
+        return Some(0);
+    }
+    // "mahomes" or "patr mahomes" for Patrick Mahomes
+    let name_words: Vec<&str> = name.split(' ').collect();
+    if query
+        .split(' ')
+        .all(|q| name_words.iter().any(|n| n.starts_with(q)))
+    {
+        return Some(1);
+    }
+    // otherwise allow a typo or two, more for longer names, in either the whole name or
+    // just one part of it
+    let compact = |s: &str| s.replace(' ', "");
+    let query = compact(&query);
+    let distance = name_words
+        .iter()
+        .map(|n| levenshtein(&query, n))
+        .chain(std::iter::once(levenshtein(&query, &compact(&name))))
+        .min()
+        .unwrap_or(usize::MAX);
+    if distance <= (query.len() / 4).max(1) {
+        Some(1 + distance)
+    } else {
+        None
+    }
+}
+
+// The closest candidate to the query.  Candidates are (name, rank, value), and ties go to the
+// lowest rank, e.g. Sleeper's search rank.
+pub fn best_match<'a, T>(
+    query: &str,
+    candidates: impl IntoIterator<Item = (&'a str, u64, T)>,
+) -> Option<T> {
+    candidates
+        .into_iter()
+        .filter_map(|(name, rank, value)| match_distance(query, name).map(|d| (d, rank, value)))
+        .min_by_key(|(distance, rank, _)| (*distance, *rank))
+        .map(|(_, _, value)| value)
+}
+
+// The team that has a player, by their ID in the league's own system.
+pub fn rostered_by<'a>(player_id: &str, rosters: &'a [FantasyRoster]) -> Option<&'a FantasyTeam> {
+    rosters
+        .iter()
+        .find(|r| r.players().any(|p| p.id == player_id))
+        .map(|r| &r.team)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous + if ca == *cb { 0 } else { 1 };
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(previous + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::FantasyPlayer;
+
+    #[test]
+    fn matches_partial_names_and_typos() {
+        let players = vec![
+            ("Patrick Mahomes", 1, "mahomes"),
+            ("D.K. Metcalf", 1, "metcalf"),
+            ("Travis Kelce", 1, "travis"),
+            ("Jason Kelce", 2, "jason"),
+        ];
+        assert_eq!(best_match("pat mahomes", players.clone()), Some("mahomes"));
+        assert_eq!(best_match("dk metcalf", players.clone()), Some("metcalf"));
+        assert_eq!(best_match("mahommes", players.clone()), Some("mahomes"));
+        assert_eq!(best_match("kelce", players.clone()), Some("travis"));
+        assert_eq!(best_match("jason kelce", players.clone()), Some("jason"));
+        assert_eq!(best_match("nobody", players), None);
+    }
+
+    #[test]
+    fn ignores_suffixes_punctuation_and_nicknames() {
+        let players = vec![
+            ("Michael Pittman Jr.", 1, "pittman"),
+            ("Amon-Ra St. Brown", 1, "st brown"),
+            ("Ja'Marr Chase", 1, "chase"),
+            ("Mike Williams", 5, "williams"),
+            ("Mike Williams", 1, "other williams"),
+        ];
+        assert_eq!(best_match("mike pittman", players.clone()), Some("pittman"));
+        assert_eq!(
+            best_match("amon ra st brown", players.clone()),
+            Some("st brown")
+        );
+        assert_eq!(best_match("jamarr chase", players.clone()), Some("chase"));
+        assert_eq!(
+            best_match("michael williams", players),
+            Some("other williams")
+        );
+    }
+
+    #[test]
+    fn finds_who_rosters_a_player() {
+        let player = |id: &str| FantasyPlayer {
+            id: id.to_string(),
+            name: id.to_string(),
+            position: "RB".to_string(),
+            nfl_team: None,
+            injury_status: None,
+        };
+        let rosters = vec![FantasyRoster {
+            team: FantasyTeam::test("1"),
+            starters: vec![player("100")],
+            bench: vec![player("200")],
+            reserve: vec![],
+            taxi: vec![player("250")],
+        }];
+        assert_eq!(rostered_by("200", &rosters).unwrap().team_name, "Team 1");
+        assert_eq!(rostered_by("250", &rosters).unwrap().team_name, "Team 1");
+        assert!(rostered_by("300", &rosters).is_none());
+    }
+}
@@ -0,0 +1,450 @@
+use super::fantasy_client::{
+    FantasyMatchup, FantasyStanding, FantasyTeam, PlayoffFormat, Tiebreaker,
+};
+use std::collections::HashMap;
+
+pub const SIMULATIONS: u32 = 10000;
+
+// used for teams that haven't played enough to say how they score
+const MIN_GAMES: usize = 2;
+
+// How a team's weekly score is spread, from the games it's played.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Scoring {
+    pub mean: f64,
+    pub std_dev: f64,
+}
+
+impl Scoring {
+    fn from_scores(scores: &[f64]) -> Scoring {
+        let n = scores.len().max(1) as f64;
+        let mean = scores.iter().sum::<f64>() / n;
+        let variance = scores.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n;
+        Scoring {
+            mean,
+            std_dev: variance.sqrt(),
+        }
+    }
+}
+
+// Each team's scoring by team ID.  Teams without much history get the league-wide numbers.
+pub fn scoring(teams: &[FantasyTeam], played: &[FantasyMatchup]) -> HashMap<String, Scoring> {
+    let mut scores: HashMap<&str, Vec<f64>> = HashMap::new();
+    for m in played.iter() {
+        if let (Some(s1), Some(s2)) = (m.score1, m.score2) {
+            scores.entry(&m.team1.id).or_default().push(s1);
+            scores.entry(&m.team2.id).or_default().push(s2);
+        }
+    }
+    let all: Vec<f64> = scores.values().flatten().copied().collect();
+    let league = Scoring::from_scores(&all);
+    teams
+        .iter()
+        .map(|t| {
+            let scoring = match scores.get(t.id.as_str()) {
+                Some(s) if s.len() >= MIN_GAMES => Scoring::from_scores(s),
+                _ => league,
+            };
+            (t.id.clone(), scoring)
+        })
+        .collect()
+}
+
+// Where a league's season stands, and what's left of it.
+pub struct Season<'a> {
+    pub standings: &'a [FantasyStanding],
+    // finished games, for head-to-head tiebreakers
+    pub played: &'a [FantasyMatchup],
+    // regular season games still to come; their scores are ignored
+    pub remaining: &'a [FantasyMatchup],
+    pub scoring: &'a HashMap<String, Scoring>,
+    pub format: &'a PlayoffFormat,
+}
+
+// Each team's chances, from 0 to 1.
+pub struct PlayoffOdds {
+    pub team: FantasyTeam,
+    pub playoffs: f64,
+    pub bye: f64,
+    pub title: f64,
+}
+
+// SplitMix64, so a seed gives the same results everywhere.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    // uniform in [0, 1)
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    // Box-Muller
+    fn normal(&mut self, mean: f64, std_dev: f64) -> f64 {
+        let u1 = 1.0 - self.next_f64();
+        let u2 = self.next_f64();
+        mean + std_dev * (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+// 1 for a win, 0 for a loss and a half for a tie
+fn result(score: f64, other: f64) -> f64 {
+    if score > other {
+        1.0
+    } else if score < other {
+        0.0
+    } else {
+        0.5
+    }
+}
+
+// One run through the season, with teams referred to by their index in the standings.
+#[derive(Clone)]
+struct Table {
+    win_totals: Vec<f64>,
+    games: Vec<u32>,
+    points_for: Vec<f64>,
+    // (team, opponent) -> wins against them, ties counting half
+    head_to_head: HashMap<(usize, usize), f64>,
+}
+
+impl Table {
+    fn add_head_to_head(&mut self, a: usize, b: usize, score_a: f64, score_b: f64) {
+        *self.head_to_head.entry((a, b)).or_default() += result(score_a, score_b);
+        *self.head_to_head.entry((b, a)).or_default() += result(score_b, score_a);
+    }
+
+    fn record(&mut self, a: usize, b: usize, score_a: f64, score_b: f64) {
+        for (team, score, other) in [(a, score_a, score_b), (b, score_b, score_a)] {
+            self.win_totals[team] += result(score, other);
+            self.games[team] += 1;
+            self.points_for[team] += score;
+        }
+        self.add_head_to_head(a, b, score_a, score_b);
+    }
+
+    fn win_pct(&self, team: usize) -> f64 {
+        self.win_totals[team] / self.games[team].max(1) as f64
+    }
+
+    // Share of games a team has won against the rest of a group.
+    fn head_to_head_pct(&self, team: usize, group: &[usize]) -> f64 {
+        let (mut won, mut games) = (0.0, 0.0);
+        for other in group.iter().filter(|o| **o != team) {
+            let wins = self
+                .head_to_head
+                .get(&(team, *other))
+                .copied()
+                .unwrap_or(0.0);
+            let losses = self
+                .head_to_head
+                .get(&(*other, team))
+                .copied()
+                .unwrap_or(0.0);
+            won += wins;
+            games += wins + losses;
+        }
+        if games == 0.0 {
+            0.5
+        } else {
+            won / games
+        }
+    }
+
+    // Team indexes from first place down.
+    fn seeds(&self, tiebreaker: Tiebreaker) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.win_totals.len()).collect();
+        let by_points = |a: &usize, b: &usize| {
+            self.points_for[*b]
+                .partial_cmp(&self.points_for[*a])
+                .unwrap()
+        };
+        order.sort_by(|a, b| {
+            self.win_pct(*b)
+                .partial_cmp(&self.win_pct(*a))
+                .unwrap()
+                .then(by_points(a, b))
+        });
+        if tiebreaker == Tiebreaker::HeadToHead {
+            let mut start = 0;
+            while start < order.len() {
+                let pct = self.win_pct(order[start]);
+                let end = start
+                    + order[start..]
+                        .iter()
+                        .take_while(|t| self.win_pct(**t) == pct)
+                        .count();
+                let group = order[start..end].to_vec();
+                order[start..end].sort_by(|a, b| {
+                    self.head_to_head_pct(*b, &group)
+                        .partial_cmp(&self.head_to_head_pct(*a, &group))
+                        .unwrap()
+                        .then(by_points(a, b))
+                });
+                start = end;
+            }
+        }
+        order
+    }
+}
+
+// Plays out the rest of the season many times over, counting how often each team makes the
+// playoffs, gets a bye and wins it all.  The same seed always gives the same odds.
+pub fn simulate(season: &Season, simulations: u32, seed: u64) -> Vec<PlayoffOdds> {
+    let standings = season.standings;
+    let format = season.format;
+    let index: HashMap<&str, usize> = standings
+        .iter()
+        .enumerate()
+        .map(|(i, s)| (s.team.id.as_str(), i))
+        .collect();
+    let pair = |m: &FantasyMatchup| {
+        Some((
+            *index.get(m.team1.id.as_str())?,
+            *index.get(m.team2.id.as_str())?,
+        ))
+    };
+    let scoring: Vec<Scoring> = standings
+        .iter()
+        .map(|s| {
+            season.scoring.get(&s.team.id).copied().unwrap_or(Scoring {
+                mean: 0.0,
+                std_dev: 0.0,
+            })
+        })
+        .collect();
+
+    let mut table = Table {
+        win_totals: standings
+            .iter()
+            .map(|s| s.wins as f64 + s.ties as f64 / 2.0)
+            .collect(),
+        games: standings
+            .iter()
+            .map(|s| s.wins + s.losses + s.ties)
+            .collect(),
+        points_for: standings.iter().map(|s| s.points_for).collect(),
+        head_to_head: HashMap::new(),
+    };
+    // only head-to-head is wanted from past games, the standings already have the rest
+    for m in season.played.iter() {
+        if let (Some((a, b)), Some(s1), Some(s2)) = (pair(m), m.score1, m.score2) {
+            table.add_head_to_head(a, b, s1, s2);
+        }
+    }
+    let remaining: Vec<(usize, usize)> = season.remaining.iter().filter_map(pair).collect();
+
+    let playoff_teams = (format.teams as usize).min(standings.len());
+    let byes = (format.byes as usize).min(playoff_teams);
+    let mut rng = Rng(seed);
+    let mut counts = vec![(0u32, 0u32, 0u32); standings.len()];
+    let score = |rng: &mut Rng, team: usize| {
+        rng.normal(scoring[team].mean, scoring[team].std_dev)
+            .max(0.0)
+    };
+
+    for _ in 0..simulations {
+        let mut sim = table.clone();
+        for (a, b) in remaining.iter() {
+            let (score_a, score_b) = (score(&mut rng, *a), score(&mut rng, *b));
+            sim.record(*a, *b, score_a, score_b);
+        }
+        let seeds = sim.seeds(format.tiebreaker);
+        for (i, team) in seeds.iter().take(playoff_teams).enumerate() {
+            counts[*team].0 += 1;
+            if i < byes {
+                counts[*team].1 += 1;
+            }
+        }
+        if playoff_teams == 0 {
+            continue;
+        }
+
+        // seeds are 0-based here; the best seed left plays the worst each round, and ties go
+        // to the better seed
+        let mut alive: Vec<usize> = (0..playoff_teams).collect();
+        let mut round = alive.split_off(byes);
+        while round.len() + alive.len() > 1 {
+            for i in 0..round.len() / 2 {
+                let (high, low) = (round[i], round[round.len() - 1 - i]);
+                let upset = score(&mut rng, seeds[low]) > score(&mut rng, seeds[high]);
+                alive.push(if upset { low } else { high });
+            }
+            if round.len() % 2 == 1 {
+                alive.push(round[round.len() / 2]);
+            }
+            alive.sort_unstable();
+            round = std::mem::take(&mut alive);
+        }
+        counts[seeds[round[0]]].2 += 1;
+    }
+
+    let mut odds: Vec<PlayoffOdds> = standings
+        .iter()
+        .zip(counts.iter())
+        .map(|(s, (playoffs, bye, title))| PlayoffOdds {
+            team: s.team.clone(),
+            playoffs: *playoffs as f64 / simulations.max(1) as f64,
+            bye: *bye as f64 / simulations.max(1) as f64,
+            title: *title as f64 / simulations.max(1) as f64,
+        })
+        .collect();
+    odds.sort_by(|a, b| {
+        b.playoffs
+            .partial_cmp(&a.playoffs)
+            .unwrap()
+            .then(b.title.partial_cmp(&a.title).unwrap())
+    });
+    odds
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn standing(id: &str, wins: u32, losses: u32, points_for: f64) -> FantasyStanding {
+        FantasyStanding {
+            team: FantasyTeam::test(id),
+            wins,
+            losses,
+            ties: 0,
+            points_for,
+            points_against: 0.0,
+        }
+    }
+
+    fn even(mean: f64) -> Scoring {
+        Scoring {
+            mean,
+            std_dev: 15.0,
+        }
+    }
+
+    fn odds_for<'a>(odds: &'a [PlayoffOdds], id: &str) -> &'a PlayoffOdds {
+        odds.iter().find(|o| o.team.id == id).unwrap()
+    }
+
+    #[test]
+    fn same_seed_same_odds() {
+        let standings = vec![
+            standing("A", 10, 2, 1400.0),
+            standing("B", 7, 5, 1350.0),
+            standing("C", 6, 6, 1300.0),
+            standing("D", 5, 7, 1250.0),
+            standing("E", 2, 10, 1200.0),
+            standing("F", 6, 6, 1310.0),
+        ];
+        let remaining = vec![
+            FantasyMatchup::test(13, "A", "B", None),
+            FantasyMatchup::test(13, "C", "D", None),
+            FantasyMatchup::test(13, "E", "F", None),
+            FantasyMatchup::test(14, "A", "C", None),
+            FantasyMatchup::test(14, "B", "E", None),
+            FantasyMatchup::test(14, "D", "F", None),
+        ];
+        let scoring: HashMap<String, Scoring> = standings
+            .iter()
+            .map(|s| (s.team.id.clone(), even(110.0)))
+            .collect();
+        let format = PlayoffFormat::new(4, Tiebreaker::PointsFor);
+        let season = Season {
+            standings: &standings,
+            played: &[],
+            remaining: &remaining,
+            scoring: &scoring,
+            format: &format,
+        };
+
+        let first = simulate(&season, 2000, 42);
+        let second = simulate(&season, 2000, 42);
+        for (a, b) in first.iter().zip(second.iter()) {
+            assert_eq!(a.team.id, b.team.id);
+            assert_eq!(a.playoffs, b.playoffs);
+            assert_eq!(a.title, b.title);
+        }
+        // four teams make it and one wins every time
+        let playoffs: f64 = first.iter().map(|o| o.playoffs).sum();
+        let titles: f64 = first.iter().map(|o| o.title).sum();
+        assert!((playoffs - 4.0).abs() < 1e-9);
+        assert!((titles - 1.0).abs() < 1e-9);
+        assert_eq!(format.byes, 0);
+        // A has clinched and E can't catch anyone
+        assert_eq!(odds_for(&first, "A").playoffs, 1.0);
+        assert_eq!(odds_for(&first, "E").playoffs, 0.0);
+    }
+
+    #[test]
+    fn head_to_head_tiebreaker_and_byes() {
+        // B and C finish tied for the last spot, but C won their game despite fewer points
+        let standings = vec![
+            standing("A", 10, 0, 1500.0),
+            standing("B", 6, 4, 1400.0),
+            standing("C", 6, 4, 1100.0),
+            standing("D", 0, 10, 900.0),
+        ];
+        let played = vec![FantasyMatchup::test(3, "B", "C", Some((90.0, 100.0)))];
+        let scoring: HashMap<String, Scoring> = standings
+            .iter()
+            .map(|s| (s.team.id.clone(), even(100.0)))
+            .collect();
+        let head_to_head = PlayoffFormat::new(2, Tiebreaker::HeadToHead);
+        let season = Season {
+            standings: &standings,
+            played: &played,
+            remaining: &[],
+            scoring: &scoring,
+            format: &head_to_head,
+        };
+        let odds = simulate(&season, 100, 7);
+        assert_eq!(odds_for(&odds, "C").playoffs, 1.0);
+        assert_eq!(odds_for(&odds, "B").playoffs, 0.0);
+
+        let points = PlayoffFormat::new(2, Tiebreaker::PointsFor);
+        let odds = simulate(
+            &Season {
+                format: &points,
+                ..season
+            },
+            100,
+            7,
+        );
+        assert_eq!(odds_for(&odds, "B").playoffs, 1.0);
+
+        let with_bye = PlayoffFormat::new(3, Tiebreaker::PointsFor);
+        assert_eq!(with_bye.byes, 1);
+        let odds = simulate(
+            &Season {
+                format: &with_bye,
+                ..season
+            },
+            100,
+            7,
+        );
+        assert_eq!(odds_for(&odds, "A").bye, 1.0);
+        assert_eq!(odds_for(&odds, "B").bye, 0.0);
+    }
+
+    #[test]
+    fn league_scoring_for_teams_without_history() {
+        let teams = vec![
+            FantasyTeam::test("A"),
+            FantasyTeam::test("B"),
+            FantasyTeam::test("C"),
+        ];
+        let played = vec![
+            FantasyMatchup::test(1, "A", "B", Some((100.0, 80.0))),
+            FantasyMatchup::test(2, "A", "B", Some((120.0, 80.0))),
+        ];
+        let scoring = super::scoring(&teams, &played);
+        assert_eq!(scoring["A"].mean, 110.0);
+        assert_eq!(scoring["A"].std_dev, 10.0);
+        assert_eq!(scoring["C"].mean, 95.0);
+    }
+}
@@ -0,0 +1,141 @@
+use super::fantasy_client::{
+    FantasyLineup, FantasyMatchup, FantasyStanding, FantasyTeam, PastMatchup,
+};
+use super::history;
+
+// Rough week-to-week spread of a fantasy team's score around its projection.
+const TEAM_SCORE_STDDEV: f64 = 25.0;
+
+pub struct TeamPreview {
+    pub team: FantasyTeam,
+    pub record: Option<String>,
+    pub projected: Option<f64>,
+}
+
+pub struct MatchupPreview {
+    pub team1: TeamPreview,
+    pub team2: TeamPreview,
+    // (team1 wins, team2 wins, ties) in every earlier meeting of their owners, as /h2h counts them
+    pub head_to_head: (u32, u32, u32),
+    // chance that team1 wins
+    pub win_probability: Option<f64>,
+}
+
+pub fn build(
+    matchups: &[FantasyMatchup],
+    lineups: &[FantasyLineup],
+    standings: &[FantasyStanding],
+    history: &[PastMatchup],
+) -> Vec<MatchupPreview> {
+    let team_preview = |team: &FantasyTeam| TeamPreview {
+        team: team.clone(),
+        record: standings.iter().find(|s| s.team.id == team.id).map(|s| {
+            if s.ties > 0 {
+                format!("{}-{}-{}", s.wins, s.losses, s.ties)
+            } else {
+                format!("{}-{}", s.wins, s.losses)
+            }
+        }),
+        projected: lineups
+            .iter()
+            .find(|l| l.team.id == team.id)
+            .and_then(|l| l.projected_total()),
+    };
+
+    matchups
+        .iter()
+        .map(|m| {
+            let team1 = team_preview(&m.team1);
+            let team2 = team_preview(&m.team2);
+            let win_probability = match (team1.projected, team2.projected) {
+                (Some(p1), Some(p2)) => Some(win_probability(p1, p2)),
+                _ => None,
+            };
+            MatchupPreview {
+                head_to_head: head_to_head(history, &m.team1, &m.team2),
+                team1,
+                team2,
+                win_probability,
+            }
+        })
+        .collect()
+}
+
+fn head_to_head(
+    history: &[PastMatchup],
+    team1: &FantasyTeam,
+    team2: &FantasyTeam,
+) -> (u32, u32, u32) {
+    match (
+        history::owner_of(history, team1),
+        history::owner_of(history, team2),
+    ) {
+        (Some(owner1), Some(owner2)) => {
+            let record = history::head_to_head(history, &owner1, &owner2).record;
+            (record.wins, record.losses, record.ties)
+        }
+        _ => (0, 0, 0),
+    }
+}
+
+// Treats each score as normally distributed around its projection.
+pub fn win_probability(projected1: f64, projected2: f64) -> f64 {
+    let stddev = (2.0 * TEAM_SCORE_STDDEV * TEAM_SCORE_STDDEV).sqrt();
+    normal_cdf((projected1 - projected2) / stddev)
+}
+
+fn normal_cdf(x: f64) -> f64 {
+    0.5 * (1.0 + erf(x / std::f64::consts::SQRT_2))
+}
+
+// Abramowitz and Stegun 7.1.26, good to about 1e-7
+fn erf(x: f64) -> f64 {
+    let sign = if x < 0.0 { -1.0 } else { 1.0 };
+    let x = x.abs();
+    let t = 1.0 / (1.0 + 0.3275911 * x);
+    let y = 1.0
+        - (((((1.061405429 * t - 1.453152027) * t) + 1.421413741) * t - 0.284496736) * t
+            + 0.254829592)
+            * t
+            * (-x * x).exp();
+    sign * y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::PastTeam;
+
+    #[test]
+    fn win_probability_is_symmetric() {
+        assert!((win_probability(100.0, 100.0) - 0.5).abs() < 1e-6);
+        let favorite = win_probability(120.0, 100.0);
+        assert!(favorite > 0.7 && favorite < 0.75);
+        assert!((favorite + win_probability(100.0, 120.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn head_to_head_follows_owners_across_seasons() {
+        // Sleeper teams go by their owner's ID; ESPN's are found by the owner's name
+        let a = PastTeam::test("A", "Old A");
+        let b = PastTeam::test("Owner B", "Team B");
+        let c = PastTeam::test("C", "Team C");
+        let history = vec![
+            PastMatchup::test("2020", 1, &a, &b, 100.0, 90.0),
+            PastMatchup::test("2020", 2, &b, &a, 110.0, 90.0),
+            PastMatchup {
+                playoff: true,
+                ..PastMatchup::test("2021", 15, &a, &b, 95.0, 80.0)
+            },
+            PastMatchup::test("2021", 3, &a, &c, 95.0, 80.0),
+        ];
+        let team_a = FantasyTeam::test("a");
+        let team_b = FantasyTeam::test("B");
+        assert_eq!(head_to_head(&history, &team_a, &team_b), (2, 1, 0));
+        assert_eq!(head_to_head(&history, &team_b, &team_a), (1, 2, 0));
+        assert_eq!(
+            head_to_head(&history, &team_a, &FantasyTeam::test("D")),
+            (0, 0, 0)
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use super::fantasy_client::{FantasyLineup, FantasyMatchup, PowerRankings};
+
+// Placeholders: {league}, {week}, {results}, {high_score}, {low_score}, {blowout},
+// {closest}, {bench}, {power}
+pub const DEFAULT_TEMPLATE: &str = "**{league} week {week} recap**
+
+__Results__
+{results}
+
+🔥 High score: {high_score}
+🧊 Low score: {low_score}
+💥 Biggest blowout: {blowout}
+😬 Closest game: {closest}
+🪑 Left on the bench: {bench}
+
+__Power rankings__
+{power}";
+
+pub struct TeamScore {
+    pub team_name: String,
+    pub score: f64,
+}
+
+pub struct GameResult {
+    // team1 and team2 for a tie
+    pub winner: TeamScore,
+    pub loser: TeamScore,
+    pub tie: bool,
+}
+
+impl GameResult {
+    fn margin(&self) -> f64 {
+        self.winner.score - self.loser.score
+    }
+}
+
+pub struct BenchPerformance {
+    pub team_name: String,
+    pub player_name: String,
+    pub points: f64,
+}
+
+pub struct PowerMove {
+    pub team_name: String,
+    pub rank: usize,
+    pub previous_rank: Option<usize>,
+}
+
+pub struct WeekRecap {
+    pub week_num: u32,
+    pub results: Vec<GameResult>,
+    pub bench: Option<BenchPerformance>,
+    pub power: Vec<PowerMove>,
+}
+
+impl WeekRecap {
+    pub fn new(
+        week_num: u32,
+        matchups: &[FantasyMatchup],
+        lineups: &[FantasyLineup],
+        previous_power: Option<&PowerRankings>,
+        power: Option<&PowerRankings>,
+    ) -> WeekRecap {
+        let results = matchups
+            .iter()
+            .map(|m| {
+                let one = TeamScore {
+                    team_name: m.team1.team_name.clone(),
+                    score: m.score1.unwrap_or(0.0),
+                };
+                let two = TeamScore {
+                    team_name: m.team2.team_name.clone(),
+                    score: m.score2.unwrap_or(0.0),
+                };
+                let tie = one.score == two.score;
+                if one.score >= two.score {
+                    GameResult {
+                        winner: one,
+                        loser: two,
+                        tie,
+                    }
+                } else {
+                    GameResult {
+                        winner: two,
+                        loser: one,
+                        tie,
+                    }
+                }
+            })
+            .collect();
+
+        let bench = lineups
+            .iter()
+            .flat_map(|l| l.bench.iter().map(move |p| (l, p)))
+            .max_by(|a, b| a.1.points.partial_cmp(&b.1.points).unwrap())
+            .map(|(l, p)| BenchPerformance {
+                team_name: l.team.team_name.clone(),
+                player_name: p.player.name.clone(),
+                points: p.points,
+            });
+
+        let power = power.map_or(vec![], |power| {
+            power
+                .power
+                .iter()
+                .enumerate()
+                .map(|(i, t)| PowerMove {
+                    team_name: t.team.clone(),
+                    rank: i + 1,
+                    previous_rank: previous_power.and_then(|prev| {
+                        prev.power
+                            .iter()
+                            .position(|p| p.team == t.team)
+                            .map(|r| r + 1)
+                    }),
+                })
+                .collect()
+        });
+
+        WeekRecap {
+            week_num,
+            results,
+            bench,
+            power,
+        }
+    }
+
+    fn scores(&self) -> impl Iterator<Item = &TeamScore> {
+        self.results
+            .iter()
+            .flat_map(|r| vec![&r.winner, &r.loser].into_iter())
+    }
+
+    pub fn high_score(&self) -> Option<&TeamScore> {
+        self.scores()
+            .max_by(|a, b| a.score.partial_cmp(&b.score).unwrap())
+    }
+
+    pub fn low_score(&self) -> Option<&TeamScore> {
+        self.scores()
+            .min_by(|a, b| a.score.partial_cmp(&b.score).unwrap())
+    }
+
+    pub fn blowout(&self) -> Option<&GameResult> {
+        self.results
+            .iter()
+            .max_by(|a, b| a.margin().partial_cmp(&b.margin()).unwrap())
+    }
+
+    pub fn closest(&self) -> Option<&GameResult> {
+        self.results
+            .iter()
+            .min_by(|a, b| a.margin().partial_cmp(&b.margin()).unwrap())
+    }
+
+    pub fn render(&self, league_name: &str, template: &str) -> String {
+        let team_score = |t: Option<&TeamScore>| {
+            t.map_or("-".to_string(), |t| {
+                format!("{} ({:.2})", t.team_name, t.score)
+            })
+        };
+        let game = |g: Option<&GameResult>| {
+            g.map_or("-".to_string(), |g| {
+                if g.tie {
+                    format!(
+                        "{} and {} tied at {:.2}",
+                        g.winner.team_name, g.loser.team_name, g.winner.score
+                    )
+                } else {
+                    format!(
+                        "{} over {} by {:.2}",
+                        g.winner.team_name,
+                        g.loser.team_name,
+                        g.margin()
+                    )
+                }
+            })
+        };
+        let results: Vec<String> = self
+            .results
+            .iter()
+            .map(|r| {
+                // nobody's name is in bold for a tie
+                let bold = if r.tie { "" } else { "**" };
+                format!(
+                    "{}{}{} {:.2} - {:.2} {}",
+                    bold,
+                    r.winner.team_name,
+                    bold,
+                    r.winner.score,
+                    r.loser.score,
+                    r.loser.team_name
+                )
+            })
+            .collect();
+        let bench = self.bench.as_ref().map_or("-".to_string(), |b| {
+            format!("{} ({}, {:.2})", b.player_name, b.team_name, b.points)
+        });
+        let power: Vec<String> = self
+            .power
+            .iter()
+            .map(|p| {
+                let movement = match p.previous_rank {
+                    Some(prev) if prev > p.rank => format!(" ⬆️{}", prev - p.rank),
+                    Some(prev) if prev < p.rank => format!(" ⬇️{}", p.rank - prev),
+                    Some(_) => " ➖".to_string(),
+                    None => "".to_string(),
+                };
+                format!("{}. {}{}", p.rank, p.team_name, movement)
+            })
+            .collect();
+
+        template
+            .replace("{league}", league_name)
+            .replace("{week}", &self.week_num.to_string())
+            .replace("{results}", &results.join("\n"))
+            .replace("{high_score}", &team_score(self.high_score()))
+            .replace("{low_score}", &team_score(self.low_score()))
+            .replace("{blowout}", &game(self.blowout()))
+            .replace("{closest}", &game(self.closest()))
+            .replace("{bench}", &bench)
+            .replace(
+                "{power}",
+                if power.is_empty() {
+                    "-".to_string()
+                } else {
+                    power.join("\n")
+                }
+                .as_str(),
+            )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::{FantasyPlayer, FantasyTeam, PlayerScore, TeamPower};
+
+    fn power(teams: &[&str]) -> PowerRankings {
+        PowerRankings {
+            power: teams
+                .iter()
+                .map(|t| TeamPower {
+                    power: "1.0".to_string(),
+                    team: t.to_string(),
+                })
+                .collect(),
+            updated: "now".to_string(),
+        }
+    }
+
+    #[test]
+    fn recap_superlatives() {
+        let matchups = vec![
+            FantasyMatchup::test(4, "A", "B", Some((120.0, 80.0))),
+            FantasyMatchup::test(4, "C", "D", Some((101.0, 102.5))),
+        ];
+        let lineups = vec![FantasyLineup {
+            team: FantasyTeam::test("B"),
+            starters: vec![],
+            bench: vec![PlayerScore {
+                player: FantasyPlayer {
+                    id: "1".to_string(),
+                    name: "Benchy McBenchface".to_string(),
+                    position: "WR".to_string(),
+                    nfl_team: None,
+                    injury_status: None,
+                },
+                points: 31.5,
+                projected: None,
+            }],
+        }];
+        let prev = power(&["Team A", "Team B", "Team C", "Team D"]);
+        let now = power(&["Team B", "Team A", "Team C", "Team D"]);
+        let recap = WeekRecap::new(4, &matchups, &lineups, Some(&prev), Some(&now));
+
+        assert_eq!(recap.high_score().unwrap().team_name, "Team A");
+        assert_eq!(recap.low_score().unwrap().team_name, "Team B");
+        assert_eq!(recap.blowout().unwrap().winner.team_name, "Team A");
+        assert_eq!(recap.closest().unwrap().winner.team_name, "Team D");
+
+        let text = recap.render("Test", "{week}|{bench}|{power}");
+        assert_eq!(
+            text,
+            "4|Benchy McBenchface (Team B, 31.50)|1. Team B ⬆️1\n2. Team A ⬇️1\n3. Team C ➖\n4. Team D ➖"
+        );
+    }
+
+    #[test]
+    fn ties_have_no_winner() {
+        let matchups = vec![
+            FantasyMatchup::test(4, "A", "B", Some((120.0, 80.0))),
+            FantasyMatchup::test(4, "C", "D", Some((99.5, 99.5))),
+        ];
+        let recap = WeekRecap::new(4, &matchups, &[], None, None);
+        assert!(recap.closest().unwrap().tie);
+        let text = recap.render("Test", "{results}|{closest}");
+        assert_eq!(
+            text,
+            "**Team A** 120.00 - 80.00 Team B\nTeam C 99.50 - 99.50 Team D|Team C and Team D tied at 99.50"
+        );
+    }
+}
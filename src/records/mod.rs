@@ -0,0 +1,304 @@
+use super::fantasy_client::{PastMatchup, PastTeam};
+use super::storage::{league_key, Storage};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct TeamWeek {
+    pub team: PastTeam,
+    pub season: String,
+    pub week_num: u32,
+    pub score: f64,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct Streak {
+    // as of the streak's latest game
+    pub team: PastTeam,
+    pub length: u32,
+    pub start_season: String,
+    pub start_week: u32,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct SeasonTotal {
+    pub team: PastTeam,
+    pub season: String,
+    pub points: f64,
+}
+
+#[derive(Clone, Deserialize, Serialize, Debug)]
+pub struct Champion {
+    pub season: String,
+    pub team: PastTeam,
+}
+
+pub enum NewRecord {
+    HighScore(TeamWeek),
+    LowScore(TeamWeek),
+    Margin(PastMatchup),
+    WinStreak(Streak),
+    LossStreak(Streak),
+    SeasonPoints(SeasonTotal),
+    Champion(Champion),
+}
+
+// The league's all-time records, kept up to date one finished week at a time.
+#[derive(Default, Deserialize, Serialize)]
+pub struct RecordBook {
+    // (season, week) of the last week counted
+    pub through: Option<(String, u32)>,
+    pub high_score: Option<TeamWeek>,
+    pub low_score: Option<TeamWeek>,
+    // with the winner as team1
+    pub biggest_margin: Option<PastMatchup>,
+    pub win_streak: Option<Streak>,
+    pub loss_streak: Option<Streak>,
+    // regular season only
+    pub season_points: Option<SeasonTotal>,
+    pub champions: Vec<Champion>,
+
+    // what's still in progress, by owner ID: each team's current streak, with true for wins,
+    // and this season's points
+    #[serde(default)]
+    streaks: HashMap<String, (bool, Streak)>,
+    #[serde(default)]
+    season_totals: HashMap<String, SeasonTotal>,
+}
+
+impl RecordBook {
+    // Counts every week in the history that the book hasn't seen yet, returning the records
+    // that fell.  Nothing is announced when the book is built from scratch.
+    pub fn catch_up(&mut self, history: &[PastMatchup]) -> Vec<NewRecord> {
+        let announce = self.through.is_some();
+        let mut weeks: BTreeMap<(String, u32), Vec<&PastMatchup>> = BTreeMap::new();
+        for m in history.iter() {
+            weeks
+                .entry((m.season.clone(), m.week_num))
+                .or_default()
+                .push(m);
+        }
+        let mut new_records = vec![];
+        for (week, games) in weeks.into_iter() {
+            if self.through.as_ref().is_some_and(|t| &week <= t) {
+                continue;
+            }
+            if self.through.as_ref().is_none_or(|t| t.0 != week.0) {
+                self.season_totals.clear();
+            }
+            new_records.extend(self.add_week(&games));
+            self.through = Some(week);
+        }
+        if announce {
+            new_records
+        } else {
+            vec![]
+        }
+    }
+
+    fn add_week(&mut self, games: &[&PastMatchup]) -> Vec<NewRecord> {
+        let mut new_records = vec![];
+        for m in games.iter() {
+            // winner first
+            let m = if m.score2 > m.score1 {
+                PastMatchup {
+                    team1: m.team2.clone(),
+                    team2: m.team1.clone(),
+                    score1: m.score2,
+                    score2: m.score1,
+                    ..(*m).clone()
+                }
+            } else {
+                (*m).clone()
+            };
+
+            for (team, score) in [(&m.team1, m.score1), (&m.team2, m.score2)] {
+                let week = TeamWeek {
+                    team: team.clone(),
+                    season: m.season.clone(),
+                    week_num: m.week_num,
+                    score,
+                };
+                if self.high_score.as_ref().is_none_or(|h| score > h.score) {
+                    self.high_score = Some(week.clone());
+                    new_records.push(NewRecord::HighScore(week.clone()));
+                }
+                if self.low_score.as_ref().is_none_or(|l| score < l.score) {
+                    self.low_score = Some(week.clone());
+                    new_records.push(NewRecord::LowScore(week));
+                }
+                if !m.playoff {
+                    let total =
+                        self.season_totals
+                            .entry(team.owner_id.clone())
+                            .or_insert(SeasonTotal {
+                                team: team.clone(),
+                                season: m.season.clone(),
+                                points: 0.0,
+                            });
+                    total.team = team.clone();
+                    total.points += score;
+                    let total = total.clone();
+                    if self
+                        .season_points
+                        .as_ref()
+                        .is_none_or(|s| total.points > s.points)
+                    {
+                        // only worth announcing the first time it's passed
+                        let passed = self.season_points.as_ref().is_none_or(|s| {
+                            s.team.owner_id != team.owner_id || s.season != m.season
+                        });
+                        self.season_points = Some(total.clone());
+                        if passed {
+                            new_records.push(NewRecord::SeasonPoints(total));
+                        }
+                    }
+                }
+            }
+
+            let margin = m.score1 - m.score2;
+            if margin > 0.0
+                && self
+                    .biggest_margin
+                    .as_ref()
+                    .is_none_or(|b| margin > b.score1 - b.score2)
+            {
+                self.biggest_margin = Some(m.clone());
+                new_records.push(NewRecord::Margin(m.clone()));
+            }
+
+            if margin > 0.0 {
+                new_records.extend(self.extend_streak(&m, &m.team1, true));
+                new_records.extend(self.extend_streak(&m, &m.team2, false));
+            } else {
+                // a tie ends both streaks
+                self.streaks.remove(&m.team1.owner_id);
+                self.streaks.remove(&m.team2.owner_id);
+            }
+
+            // the winner of the final, scored over the whole round
+            if m.championship && margin > 0.0 {
+                let champion = Champion {
+                    season: m.season.clone(),
+                    team: m.team1.clone(),
+                };
+                self.champions.retain(|c| c.season != champion.season);
+                self.champions.push(champion.clone());
+                new_records.push(NewRecord::Champion(champion));
+            }
+        }
+        new_records
+    }
+
+    fn extend_streak(&mut self, m: &PastMatchup, team: &PastTeam, won: bool) -> Option<NewRecord> {
+        let streak = match self.streaks.get_mut(&team.owner_id) {
+            Some((w, s)) if *w == won => {
+                s.length += 1;
+                s.team = team.clone();
+                s.clone()
+            }
+            _ => {
+                let s = Streak {
+                    team: team.clone(),
+                    length: 1,
+                    start_season: m.season.clone(),
+                    start_week: m.week_num,
+                };
+                self.streaks.insert(team.owner_id.clone(), (won, s.clone()));
+                s
+            }
+        };
+        let record = if won {
+            &mut self.win_streak
+        } else {
+            &mut self.loss_streak
+        };
+        if record.as_ref().is_none_or(|r| streak.length > r.length) {
+            *record = Some(streak.clone());
+            Some(if won {
+                NewRecord::WinStreak(streak)
+            } else {
+                NewRecord::LossStreak(streak)
+            })
+        } else {
+            None
+        }
+    }
+}
+
+const BOOK_KEY: &str = "records";
+
+// Brings a league's stored record book up to date with every finished week of its history.
+pub fn update(
+    storage: &Storage,
+    league_id: &str,
+    history: &[PastMatchup],
+) -> Result<(RecordBook, Vec<NewRecord>), Box<dyn Error>> {
+    let key = league_key(league_id, BOOK_KEY);
+    let mut book: RecordBook = storage.load(&key).unwrap_or_default();
+    let new_records = book.catch_up(history);
+    storage.save(&key, &book)?;
+    Ok((book, new_records))
+}
+
+// The record book as of the last `update`, without fetching anything.
+pub fn stored(storage: &Storage, league_id: &str) -> Option<RecordBook> {
+    storage.load(&league_key(league_id, BOOK_KEY))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_quietly_then_announces_new_records() {
+        let team = |owner: &str| PastTeam::test(owner, owner);
+        let (a, b, c, d) = (team("A"), team("B"), team("C"), team("D"));
+        let mut history = vec![
+            PastMatchup::test("2021", 1, &a, &b, 100.0, 90.0),
+            PastMatchup::test("2021", 1, &c, &d, 80.0, 70.0),
+            PastMatchup::test("2021", 2, &a, &c, 110.0, 60.0),
+            PastMatchup::test("2021", 2, &b, &d, 95.0, 85.0),
+        ];
+        let mut book = RecordBook::default();
+        assert!(book.catch_up(&history).is_empty());
+        assert_eq!(book.high_score.as_ref().unwrap().score, 110.0);
+        assert_eq!(book.win_streak.as_ref().unwrap().length, 2);
+        assert_eq!(book.loss_streak.as_ref().unwrap().team.owner_id, "d");
+
+        // two semifinals; nothing already counted gets counted twice, and nobody is champion
+        // until the final is played, even if one semifinal is the only playoff game that week
+        history.push(PastMatchup {
+            playoff: true,
+            ..PastMatchup::test("2021", 3, &a, &d, 150.0, 50.0)
+        });
+        history.push(PastMatchup {
+            playoff: true,
+            ..PastMatchup::test("2021", 3, &b, &c, 90.0, 100.0)
+        });
+        let new_records = book.catch_up(&history);
+        assert!(new_records
+            .iter()
+            .any(|r| matches!(r, NewRecord::HighScore(w) if w.score == 150.0)));
+        assert!(new_records
+            .iter()
+            .any(|r| matches!(r, NewRecord::Margin(m) if m.score1 - m.score2 == 100.0)));
+        assert!(book.champions.is_empty());
+        // playoff points don't count for the season
+        assert_eq!(book.season_points.as_ref().unwrap().points, 210.0);
+
+        history.push(PastMatchup {
+            playoff: true,
+            championship: true,
+            ..PastMatchup::test("2021", 4, &a, &c, 120.0, 130.0)
+        });
+        let new_records = book.catch_up(&history);
+        assert!(matches!(
+            new_records.as_slice(),
+            [NewRecord::Champion(c)] if c.team.owner_id == "c"
+        ));
+        assert!(book.catch_up(&history).is_empty());
+        assert_eq!(book.win_streak.as_ref().unwrap().length, 3);
+    }
+}
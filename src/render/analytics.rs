@@ -0,0 +1,93 @@
+use super::super::analytics::{Strength, TeamLuck, TeamSchedule};
+use super::super::fantasy_client::FantasyTeam;
+use super::{paginate, Embed, Reply};
+
+pub fn luck_embed(league_name: &str, color: u32, luck: &[TeamLuck]) -> Embed {
+    let mut embed = Embed::new(format!("{} luck", league_name), color);
+    if luck.is_empty() {
+        embed.description = Some("No games have been played yet.".to_string());
+    }
+    for (i, t) in luck.iter().enumerate() {
+        embed = embed.field(
+            format!("{}. {}", i + 1, t.team.team_name),
+            format!(
+                "**{}** · all-play {} · exp {:.2} · luck {:+.2} · PA rank {}",
+                t.record, t.all_play, t.expected_wins, t.luck, t.points_against_rank
+            ),
+            false,
+        );
+    }
+    embed.footer = Some("Luck is actual wins minus all-play expected wins".to_string());
+    embed
+}
+
+pub fn luck(league_name: &str, color: u32, luck: &[TeamLuck]) -> Vec<Reply> {
+    paginate(vec![luck_embed(league_name, color, luck)])
+}
+
+// how far opponents' all-play rate has to be from .500 to call a schedule easy or hard
+const SOS_MARGIN: f64 = 0.05;
+
+fn strength_line(strength: &Strength) -> String {
+    let path = if strength.opponent_all_play >= 0.5 + SOS_MARGIN {
+        " · 🔥 hard"
+    } else if strength.opponent_all_play <= 0.5 - SOS_MARGIN {
+        " · 🌴 easy"
+    } else {
+        ""
+    };
+    format!(
+        "opp all-play **{:.3}** · opp PPG {:.2} · {} games{}",
+        strength.opponent_all_play, strength.opponent_points, strength.games, path
+    )
+}
+
+// One side of everyone's schedule, hardest first.
+fn sos_embed<'a>(
+    title: String,
+    color: u32,
+    schedules: &'a [TeamSchedule],
+    side: impl Fn(&'a TeamSchedule) -> Option<&'a Strength>,
+) -> Embed {
+    let mut embed = Embed::new(title, color);
+    let mut teams: Vec<(&FantasyTeam, &Strength)> = schedules
+        .iter()
+        .filter_map(|t| Some((&t.team, side(t)?)))
+        .collect();
+    if teams.is_empty() {
+        embed.description = Some("No games to go on.".to_string());
+    }
+    teams.sort_by(|a, b| {
+        b.1.opponent_all_play
+            .partial_cmp(&a.1.opponent_all_play)
+            .unwrap()
+    });
+    for (i, (team, strength)) in teams.iter().enumerate() {
+        embed = embed.field(
+            format!("{}. {}", i + 1, team.team_name),
+            strength_line(strength),
+            false,
+        );
+    }
+    embed
+}
+
+pub fn sos(league_name: &str, color: u32, schedules: &[TeamSchedule]) -> Vec<Reply> {
+    let mut remaining = sos_embed(
+        format!("{} remaining schedule", league_name),
+        color,
+        schedules,
+        |t| t.remaining.as_ref(),
+    );
+    remaining.footer =
+        Some("Opponents are rated by their all-play record and points so far, counting the end of last season until a few weeks are in".to_string());
+    paginate(vec![
+        sos_embed(
+            format!("{} schedule so far", league_name),
+            color,
+            schedules,
+            |t| t.past.as_ref(),
+        ),
+        remaining,
+    ])
+}
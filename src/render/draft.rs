@@ -0,0 +1,187 @@
+use super::super::draft::{DraftGrades, PickValue};
+use super::super::fantasy_client::{Draft, DraftPick, DraftStatus, PlayerProjection};
+use super::{
+    paginate, player_line, text_block, truncate, Embed, Reply, DESCRIPTION_LIMIT, FIELD_VALUE_LIMIT,
+};
+
+// How many steals and busts to call out.
+const DRAFT_HIGHLIGHTS: usize = 5;
+
+fn pick_line(pick: &DraftPick) -> String {
+    format!(
+        "R{} #{} {} ({}){}",
+        pick.round,
+        pick.pick_no,
+        pick.player.name,
+        pick.player.position,
+        if pick.keeper { " · keeper" } else { "" }
+    )
+}
+
+// Each team's picks, in the order the teams first picked.
+pub fn draft(league_name: &str, color: u32, draft: &Draft) -> Vec<Reply> {
+    if draft.picks.is_empty() {
+        return vec![text_block("Nobody's been drafted yet.")];
+    }
+    let mut teams: Vec<(&str, Vec<&DraftPick>)> = vec![];
+    for pick in draft.picks.iter() {
+        match teams.iter_mut().find(|(id, _)| *id == pick.team.id) {
+            Some((_, picks)) => picks.push(pick),
+            None => teams.push((&pick.team.id, vec![pick])),
+        }
+    }
+    let mut embed = Embed::new(format!("{} draft", league_name), color);
+    embed.description = Some(match draft.status {
+        DraftStatus::Complete => format!("{} rounds, all done", draft.rounds),
+        _ => format!(
+            "{} of {} rounds so far",
+            draft.picks.last().map_or(0, |p| p.round),
+            draft.rounds
+        ),
+    });
+    for (_, picks) in teams.iter() {
+        let lines: Vec<String> = picks.iter().map(|p| pick_line(p)).collect();
+        embed = embed.field(
+            picks[0].team.team_name.clone(),
+            truncate(&lines.join("\n"), FIELD_VALUE_LIMIT),
+            false,
+        );
+    }
+    paginate(vec![embed])
+}
+
+// How many of the best players left to mention after each live pick.
+const BEST_AVAILABLE_SHOWN: usize = 3;
+
+// A pick as it happens, with who's still on the board, e.g.
+// "#14 (R2) Team takes **Player** (RB, NYJ)".
+pub fn live_pick(pick: &DraftPick, available: &[&PlayerProjection]) -> String {
+    let player = &pick.player;
+    let mut text = format!(
+        "**#{}** (R{}) {} takes **{}** ({}{}){}",
+        pick.pick_no,
+        pick.round,
+        pick.team.team_name,
+        player.name,
+        player.position,
+        player
+            .nfl_team
+            .as_ref()
+            .map_or(String::new(), |t| format!(", {}", t)),
+        if pick.keeper { " · keeper" } else { "" }
+    );
+    let best: Vec<String> = available
+        .iter()
+        .take(BEST_AVAILABLE_SHOWN)
+        .map(|p| format!("{} ({})", p.player.name, p.player.position))
+        .collect();
+    if !best.is_empty() {
+        text = format!("{}\nBest available: {}", text, best.join(", "));
+        if let Some(next) = available
+            .iter()
+            .find(|p| p.player.position == player.position)
+        {
+            text = format!("{} · next {}: {}", text, player.position, next.player.name);
+        }
+    }
+    text
+}
+
+// How many players the draft board lists.
+const BOARD_SIZE: usize = 30;
+
+// The best players left, from the league's rank list if `ranked`, otherwise by projection.
+pub fn board(
+    league_name: &str,
+    color: u32,
+    position: Option<&str>,
+    ranked: bool,
+    available: &[&PlayerProjection],
+) -> Vec<Reply> {
+    let title = match position {
+        Some(p) => format!("{} draft board: {}", league_name, p),
+        None => format!("{} draft board", league_name),
+    };
+    let mut embed = Embed::new(title, color);
+    embed.description = Some(if available.is_empty() {
+        "Nobody left worth drafting.".to_string()
+    } else {
+        let lines: Vec<String> = available
+            .iter()
+            .take(BOARD_SIZE)
+            .enumerate()
+            .map(|(i, p)| {
+                let projection = p
+                    .rest_of_season
+                    .map_or("-".to_string(), |r| format!("{:.1}", r));
+                format!(
+                    "{}. {} · projected {}",
+                    i + 1,
+                    player_line(&p.player),
+                    projection
+                )
+            })
+            .collect();
+        truncate(&lines.join("\n"), DESCRIPTION_LIMIT)
+    });
+    embed.footer = Some(
+        if ranked {
+            "From the league's rank list, projected under its scoring"
+        } else {
+            "By projected points under the league's scoring"
+        }
+        .to_string(),
+    );
+    paginate(vec![embed])
+}
+
+fn pick_value_line(value: &PickValue) -> String {
+    format!(
+        "{} ({}) · #{} by {} · {:.1} pts, {:+.1} for the slot",
+        value.pick.player.name,
+        value.pick.player.position,
+        value.pick.pick_no,
+        value.pick.team.team_name,
+        value.points,
+        value.surplus
+    )
+}
+
+pub fn draft_grades(league_name: &str, color: u32, grades: &DraftGrades) -> Vec<Reply> {
+    let mut embed = Embed::new(format!("{} draft grades", league_name), color);
+    for team in grades.teams.iter() {
+        let best = team.picks.first().map_or(String::new(), |p| {
+            format!("\nBest pick: {}", p.pick.player.name)
+        });
+        embed = embed.field(
+            format!("{} · {}", team.grade, team.team.team_name),
+            format!("{:+.1} points over their slots{}", team.surplus, best),
+            false,
+        );
+    }
+    let steals: Vec<String> = grades
+        .picks
+        .iter()
+        .take(DRAFT_HIGHLIGHTS)
+        .map(pick_value_line)
+        .collect();
+    let busts: Vec<String> = grades
+        .picks
+        .iter()
+        .rev()
+        .take(DRAFT_HIGHLIGHTS)
+        .map(pick_value_line)
+        .collect();
+    let highlights = Embed::new("Steals and busts".to_string(), color)
+        .field(
+            "Steals".to_string(),
+            truncate(&steals.join("\n"), FIELD_VALUE_LIMIT),
+            false,
+        )
+        .field(
+            "Busts".to_string(),
+            truncate(&busts.join("\n"), FIELD_VALUE_LIMIT),
+            false,
+        );
+    paginate(vec![embed, highlights])
+}
@@ -0,0 +1,75 @@
+use super::super::analytics::Record;
+use super::super::fantasy_client::PastMatchup;
+use super::super::history::HeadToHead;
+use super::{paginate, truncate, Embed, Reply, FIELD_VALUE_LIMIT};
+
+// e.g. "2021 week 3: 130.50-125.20", with owner1's score first
+fn meeting_line(m: &PastMatchup) -> String {
+    format!(
+        "{} week {}: {:.2}-{:.2}",
+        m.season, m.week_num, m.score1, m.score2
+    )
+}
+
+pub fn h2h(color: u32, h2h: &HeadToHead) -> Vec<Reply> {
+    let (name1, name2) = (&h2h.owner1.owner_name, &h2h.owner2.owner_name);
+    let mut embed = Embed::new(
+        format!("{} vs {}", h2h.owner1.team_name, h2h.owner2.team_name),
+        color,
+    );
+    let record = &h2h.record;
+    embed.description = Some(if record.wins + record.losses + record.ties == 0 {
+        "They've never played each other.".to_string()
+    } else if record.wins > record.losses {
+        format!("**{}** leads the series **{}**", name1, record)
+    } else if record.wins < record.losses {
+        let flipped = Record {
+            wins: record.losses,
+            losses: record.wins,
+            ties: record.ties,
+        };
+        format!("**{}** leads the series **{}**", name2, flipped)
+    } else {
+        format!("The series is tied **{}**", record)
+    });
+    embed = embed.field(
+        "All-time points".to_string(),
+        format!(
+            "{} {:.2} · {} {:.2}",
+            name1, h2h.points1, name2, h2h.points2
+        ),
+        false,
+    );
+    for (name, win) in [(name1, &h2h.biggest_win1), (name2, &h2h.biggest_win2)] {
+        if let Some(m) = win {
+            embed = embed.field(
+                format!("Biggest win by {}", name),
+                format!(
+                    "{} (by {:.2})",
+                    meeting_line(m),
+                    (m.score1 - m.score2).abs()
+                ),
+                false,
+            );
+        }
+    }
+    let playoffs: Vec<String> = h2h
+        .playoff_meetings
+        .iter()
+        .map(|m| {
+            let winner = if m.score1 > m.score2 { name1 } else { name2 };
+            format!("{} · {} won", meeting_line(m), winner)
+        })
+        .collect();
+    embed = embed.field(
+        "Playoff meetings".to_string(),
+        if playoffs.is_empty() {
+            "None yet".to_string()
+        } else {
+            truncate(&playoffs.join("\n"), FIELD_VALUE_LIMIT)
+        },
+        false,
+    );
+    embed.footer = Some(format!("Scores are {} first", name1));
+    paginate(vec![embed])
+}
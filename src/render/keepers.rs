@@ -0,0 +1,68 @@
+use super::super::fantasy_client::KeeperRules;
+use super::super::keepers::{Keeper, TeamKeepers};
+use super::{paginate, truncate, Embed, Reply, FIELD_VALUE_LIMIT};
+
+fn keeper_line(keeper: &Keeper) -> String {
+    let drafted = match keeper.drafted_round {
+        Some(r) => format!("drafted R{}", r),
+        None => "undrafted".to_string(),
+    };
+    format!(
+        "{}{} ({}) · costs R{}, {} · {:.1} pts, {:+.1} for the round",
+        if keeper.suggested { "⭐ " } else { "" },
+        keeper.player.name,
+        keeper.player.position,
+        keeper.cost,
+        drafted,
+        keeper.points,
+        keeper.value
+    )
+}
+
+pub fn keepers(
+    league_name: &str,
+    color: u32,
+    rules: &KeeperRules,
+    teams: &[TeamKeepers],
+) -> Vec<Reply> {
+    let mut embed = Embed::new(format!("{} keepers", league_name), color);
+    let escalation = match rules.round_escalation {
+        0 => "the round they were drafted in".to_string(),
+        1 => "a round earlier than they were drafted".to_string(),
+        n => format!("{} rounds earlier than they were drafted", n),
+    };
+    let undrafted = rules
+        .undrafted_round
+        .map_or("the last round".to_string(), |r| format!("R{}", r));
+    embed.description = Some(format!(
+        "Keep up to {}. Keepers cost {}, and undrafted players cost {}. Value is points over \
+         what a pick in that round got at the position. ⭐ marks each team's best keepers, each \
+         with a pick of its own; anyone else costs the pick shown only if kept instead.",
+        rules.max_keepers, escalation, undrafted
+    ));
+    for team in teams.iter() {
+        let mut lines: Vec<String> = if team.keepers.is_empty() {
+            vec!["Nobody eligible".to_string()]
+        } else {
+            team.keepers.iter().map(keeper_line).collect()
+        };
+        for pick in team.traded_away.iter() {
+            lines.push(format!(
+                "{} R{} belongs to {}",
+                pick.season, pick.round, pick.owner.team_name
+            ));
+        }
+        for pick in team.acquired.iter() {
+            lines.push(format!(
+                "Has {}'s {} R{}",
+                pick.original_team.team_name, pick.season, pick.round
+            ));
+        }
+        embed = embed.field(
+            team.team.team_name.clone(),
+            truncate(&lines.join("\n"), FIELD_VALUE_LIMIT),
+            false,
+        );
+    }
+    paginate(vec![embed])
+}
@@ -0,0 +1,309 @@
+use super::super::fantasy_client::{
+    FantasyMatchup, FantasyStanding, LeagueSettings, LeagueStatus, PowerRankings, TradeDeadline,
+    WaiverType,
+};
+use super::{
+    format_score, paginate, text_block, Embed, Reply, COLOR_GOOD, COLOR_NEUTRAL, COLOR_WARN,
+};
+use std::collections::HashMap;
+
+// a game decided by less than this is a nail-biter
+const CLOSE_GAME_MARGIN: f64 = 10.0;
+
+pub fn matchup_embed(matchup: &FantasyMatchup) -> Embed {
+    let score1 = matchup.score1.unwrap_or(0.0);
+    let score2 = matchup.score2.unwrap_or(0.0);
+    let started = score1 > 0.0 || score2 > 0.0;
+    let color = if !started {
+        COLOR_NEUTRAL
+    } else if (score1 - score2).abs() < CLOSE_GAME_MARGIN {
+        COLOR_WARN
+    } else {
+        COLOR_GOOD
+    };
+    let leader = if score2 > score1 {
+        &matchup.team2
+    } else {
+        &matchup.team1
+    };
+    let team_value = |score: Option<f64>, owner: &str, leading: bool| {
+        let score = format_score(score);
+        if started && leading {
+            format!("**{}**\n{}", score, owner)
+        } else {
+            format!("{}\n{}", score, owner)
+        }
+    };
+
+    let mut embed = Embed::new(
+        format!(
+            "{} vs. {}",
+            matchup.team1.team_name, matchup.team2.team_name
+        ),
+        color,
+    )
+    .field(
+        matchup.team1.team_name.clone(),
+        team_value(matchup.score1, &matchup.team1.owner_name, score1 >= score2),
+        true,
+    )
+    .field(
+        matchup.team2.team_name.clone(),
+        team_value(matchup.score2, &matchup.team2.owner_name, score2 >= score1),
+        true,
+    );
+    embed.thumbnail = leader.avatar_url.clone();
+    embed
+}
+
+pub fn matchups(week_num: u32, matchups: &[FantasyMatchup]) -> Vec<Reply> {
+    if matchups.is_empty() {
+        return vec![text_block(&format!("No matchups for week {}.", week_num))];
+    }
+    let mut embeds: Vec<Embed> = matchups.iter().map(matchup_embed).collect();
+    embeds[0].description = Some(format!("Week {}", week_num));
+    paginate(embeds)
+}
+
+// "QB, 2 RB, 2 WR, TE, FLEX, K, DEF, 6 BN"
+fn roster_summary(positions: &[String]) -> String {
+    let mut counts: Vec<(&str, usize)> = vec![];
+    for position in positions.iter() {
+        match counts.iter_mut().find(|(p, _)| p == position) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((position, 1)),
+        }
+    }
+    counts
+        .iter()
+        .map(|(p, count)| {
+            if *count > 1 {
+                format!("{} {}", count, p)
+            } else {
+                p.to_string()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+// The scoring rules people ask about, keyed by Sleeper's stat names.
+fn scoring_summary(scoring: &HashMap<String, f64>) -> Vec<String> {
+    let points = |stat: &str| scoring.get(stat).copied().unwrap_or(0.0);
+    let mut lines = vec![match points("rec") {
+        1.0 => "Full PPR".to_string(),
+        0.5 => "Half PPR".to_string(),
+        0.0 => "No points per reception".to_string(),
+        p => format!("{} per reception", p),
+    }];
+    for (stat, label) in [
+        ("pass_yd", "passing yards"),
+        ("rush_yd", "rushing yards"),
+        ("rec_yd", "receiving yards"),
+    ] {
+        if points(stat) > 0.0 {
+            lines.push(format!("1 per {:.0} {}", 1.0 / points(stat), label));
+        }
+    }
+    for (stat, label) in [
+        ("pass_td", "per passing TD"),
+        ("rush_td", "per rushing TD"),
+        ("rec_td", "per receiving TD"),
+        ("pass_int", "per interception thrown"),
+        ("fum_lost", "per fumble lost"),
+        ("bonus_rec_te", "extra per TE reception"),
+    ] {
+        if points(stat) != 0.0 {
+            lines.push(format!("{} {}", points(stat), label));
+        }
+    }
+    lines
+}
+
+pub fn settings(league_name: &str, color: u32, settings: &LeagueSettings) -> Vec<Reply> {
+    let status = match settings.status {
+        LeagueStatus::PreDraft => "waiting to draft",
+        LeagueStatus::Drafting => "drafting",
+        LeagueStatus::InSeason => "in season",
+        LeagueStatus::Complete => "complete",
+    };
+    let rounds: Vec<String> = settings
+        .playoff_round_weeks
+        .iter()
+        .map(|weeks| match weeks.as_slice() {
+            [first, .., last] => format!("{}-{}", first, last),
+            weeks => weeks.iter().map(|w| w.to_string()).collect(),
+        })
+        .collect();
+    let waivers = match (settings.waiver_type, settings.faab_budget) {
+        (WaiverType::Faab, Some(budget)) => format!("FAAB, ${} budget", budget),
+        (WaiverType::Faab, None) => "FAAB".to_string(),
+        (WaiverType::Rolling, _) => "Rolling priority".to_string(),
+        (WaiverType::ReverseStandings, _) => "Reverse standings, reset weekly".to_string(),
+    };
+    let trade_deadline = match &settings.trade_deadline {
+        Some(TradeDeadline::Week(week)) => format!("After week {}", week),
+        Some(TradeDeadline::Date(date)) => date.format("%b %-d, %Y").to_string(),
+        None => "None".to_string(),
+    };
+    let embed = Embed::new(format!("{} rules", league_name), color)
+        .field(
+            "Season".to_string(),
+            format!(
+                "{} · {} · {} teams",
+                settings.season, status, settings.teams
+            ),
+            false,
+        )
+        .field(
+            "Roster".to_string(),
+            roster_summary(&settings.roster_positions),
+            false,
+        )
+        .field(
+            "Scoring".to_string(),
+            scoring_summary(&settings.scoring).join("\n"),
+            false,
+        )
+        .field(
+            "Playoffs".to_string(),
+            format!(
+                "{} teams starting week {}\nRounds in weeks {}",
+                settings.playoff_teams,
+                settings.playoff_week_start,
+                rounds.join(" · ")
+            ),
+            false,
+        )
+        .field("Waivers".to_string(), waivers, true)
+        .field("Trade deadline".to_string(), trade_deadline, true);
+    paginate(vec![embed])
+}
+
+pub fn standings_embed(league_name: &str, color: u32, standings: &[FantasyStanding]) -> Embed {
+    let mut embed = Embed::new(format!("{} standings", league_name), color);
+    for (i, s) in standings.iter().enumerate() {
+        let record = if s.ties > 0 {
+            format!("{}-{}-{}", s.wins, s.losses, s.ties)
+        } else {
+            format!("{}-{}", s.wins, s.losses)
+        };
+        embed = embed.field(
+            format!("{}. {}", i + 1, s.team.team_name),
+            format!(
+                "**{}** · PF {:.2} · PA {:.2}",
+                record, s.points_for, s.points_against
+            ),
+            false,
+        );
+    }
+    embed.thumbnail = standings.first().and_then(|s| s.team.avatar_url.clone());
+    embed
+}
+
+pub fn standings(league_name: &str, color: u32, standings: &[FantasyStanding]) -> Vec<Reply> {
+    paginate(vec![standings_embed(league_name, color, standings)])
+}
+
+pub fn power_embed(league_name: &str, color: u32, power: &PowerRankings) -> Embed {
+    let mut embed = Embed::new(format!("{} power rankings", league_name), color);
+    for (i, team) in power.power.iter().enumerate() {
+        embed = embed.field(
+            format!("{}. {}", i + 1, team.team),
+            team.power.clone(),
+            false,
+        );
+    }
+    embed.footer = Some(format!("updated {}", power.updated));
+    embed
+}
+
+pub fn power(league_name: &str, color: u32, power: &PowerRankings) -> Vec<Reply> {
+    paginate(vec![power_embed(league_name, color, power)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::FantasyTeam;
+    use crate::render::COLOR_SLEEPER;
+
+    #[test]
+    fn matchup_colors_and_leader_avatar() {
+        let close = FantasyMatchup::test(3, "1", "2", Some((100.0, 105.5)));
+        let embed = matchup_embed(&close);
+        assert_eq!(embed.color, COLOR_WARN);
+        assert_eq!(
+            embed.thumbnail.as_deref(),
+            Some("https://example.com/2.png")
+        );
+        assert_eq!(embed.fields[1].value, "**105.50**\nOwner 2");
+
+        let unplayed = FantasyMatchup {
+            score1: Some(0.0),
+            score2: Some(0.0),
+            ..close
+        };
+        assert_eq!(matchup_embed(&unplayed).color, COLOR_NEUTRAL);
+    }
+
+    #[test]
+    fn standings_paginate_when_too_long() {
+        let standings: Vec<FantasyStanding> = (0..30)
+            .map(|i| FantasyStanding {
+                team: FantasyTeam::test(&i.to_string()),
+                wins: 1,
+                losses: 2,
+                ties: 0,
+                points_for: 300.0,
+                points_against: 310.0,
+            })
+            .collect();
+        let pages = super::standings("Test League", COLOR_SLEEPER, &standings[..10]);
+        assert_eq!(pages.len(), 1);
+
+        let pages = super::standings("Test League", COLOR_SLEEPER, &standings);
+        let field_counts: Vec<usize> = pages
+            .iter()
+            .map(|p| match p {
+                Reply::Embeds(e) => e.iter().map(|e| e.fields.len()).sum(),
+                Reply::Text(_) => panic!("expected embeds"),
+            })
+            .collect();
+        assert_eq!(field_counts.iter().sum::<usize>(), 30);
+        assert!(pages.iter().all(|p| match p {
+            Reply::Embeds(e) => e.iter().all(|e| e.fits()),
+            Reply::Text(_) => false,
+        }));
+    }
+
+    #[test]
+    fn settings_summaries() {
+        let positions: Vec<String> = ["QB", "RB", "RB", "WR", "WR", "TE", "FLEX", "BN", "BN"]
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(roster_summary(&positions), "QB, 2 RB, 2 WR, TE, FLEX, 2 BN");
+
+        let scoring: HashMap<String, f64> = [
+            ("rec", 0.5),
+            ("pass_yd", 0.04),
+            ("rush_yd", 0.1),
+            ("pass_td", 4.0),
+            ("pass_int", -2.0),
+        ]
+        .iter()
+        .map(|(s, p)| (s.to_string(), *p))
+        .collect();
+        assert_eq!(
+            scoring_summary(&scoring),
+            vec![
+                "Half PPR",
+                "1 per 25 passing yards",
+                "1 per 10 rushing yards",
+                "4 per passing TD",
+                "-2 per interception thrown",
+            ]
+        );
+    }
+}
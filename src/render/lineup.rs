@@ -0,0 +1,83 @@
+use super::super::fantasy_client::{FantasyPlayer, FantasyRoster, FantasyTeam};
+use super::super::lineup::{is_out, LineupIssue};
+use super::{
+    paginate, player_line, text_block, truncate, Embed, Reply, COLOR_BAD, COLOR_GOOD, COLOR_WARN,
+    DESCRIPTION_LIMIT,
+};
+
+pub fn roster_embed(roster: &FantasyRoster) -> Embed {
+    let starters_ok = roster
+        .starters
+        .iter()
+        .all(|p| !is_out(p) && !p.is_empty_slot());
+    let starters_healthy = roster.starters.iter().all(|p| p.injury_status.is_none());
+    let color = if !starters_ok {
+        COLOR_BAD
+    } else if !starters_healthy {
+        COLOR_WARN
+    } else {
+        COLOR_GOOD
+    };
+    let list = |players: &[FantasyPlayer]| {
+        if players.is_empty() {
+            "-".to_string()
+        } else {
+            players
+                .iter()
+                .map(player_line)
+                .collect::<Vec<String>>()
+                .join("\n")
+        }
+    };
+    let mut embed = Embed::new(roster.team.team_name.clone(), color)
+        .field("Starters".to_string(), list(&roster.starters), false)
+        .field("Bench".to_string(), list(&roster.bench), false);
+    if !roster.reserve.is_empty() {
+        embed = embed.field("IR".to_string(), list(&roster.reserve), false);
+    }
+    if !roster.taxi.is_empty() {
+        embed = embed.field("Taxi".to_string(), list(&roster.taxi), false);
+    }
+    embed.description = Some(roster.team.owner_name.clone());
+    embed.thumbnail = roster.team.avatar_url.clone();
+    embed
+}
+
+pub fn rosters(rosters: &[FantasyRoster]) -> Vec<Reply> {
+    if rosters.is_empty() {
+        return vec![text_block("No rosters yet.")];
+    }
+    paginate(rosters.iter().map(roster_embed).collect())
+}
+
+pub fn lineup_check_embed(week_num: u32, team: &FantasyTeam, issues: &[LineupIssue]) -> Embed {
+    let (color, description) = if issues.is_empty() {
+        (COLOR_GOOD, "✅ Lineup looks good".to_string())
+    } else {
+        (
+            COLOR_BAD,
+            issues
+                .iter()
+                .map(|i| format!("⚠️ {}", i.describe()))
+                .collect::<Vec<String>>()
+                .join("\n"),
+        )
+    };
+    let mut embed = Embed::new(team.team_name.clone(), color);
+    embed.description = Some(truncate(&description, DESCRIPTION_LIMIT));
+    embed.thumbnail = team.avatar_url.clone();
+    embed.footer = Some(format!("{} · week {}", team.owner_name, week_num));
+    embed
+}
+
+pub fn lineup_check(week_num: u32, checks: &[(FantasyTeam, Vec<LineupIssue>)]) -> Vec<Reply> {
+    if checks.is_empty() {
+        return vec![text_block("No teams to check.")];
+    }
+    paginate(
+        checks
+            .iter()
+            .map(|(team, issues)| lineup_check_embed(week_num, team, issues))
+            .collect(),
+    )
+}
@@ -0,0 +1,257 @@
+use super::fantasy_client::{FantasyPlayer, LeagueType};
+
+mod analytics;
+mod draft;
+mod history;
+mod keepers;
+mod league;
+mod lineup;
+mod nfl;
+mod players;
+mod playoffs;
+mod preview;
+mod records;
+
+pub use self::analytics::{luck, sos};
+pub use self::draft::{board, draft, draft_grades, live_pick};
+pub use self::history::h2h;
+pub use self::keepers::keepers;
+pub use self::league::{matchups, power, settings, standings};
+pub use self::lineup::{lineup_check, rosters};
+pub use self::nfl::nfl_scores;
+pub use self::players::{player_card, startsit, trending, waivers};
+pub use self::playoffs::{bracket, playoff_matchups, playoffs};
+pub use self::preview::previews;
+pub use self::records::{new_records, records};
+
+// Discord's limits, see https://discord.com/developers/docs/resources/channel#embed-limits
+pub const MESSAGE_LIMIT: usize = 2000;
+pub const EMBEDS_PER_MESSAGE: usize = 10;
+const EMBED_TOTAL_LIMIT: usize = 6000;
+const EMBED_FIELD_LIMIT: usize = 25;
+const EMBED_TITLE_LIMIT: usize = 256;
+const FIELD_NAME_LIMIT: usize = 256;
+const FIELD_VALUE_LIMIT: usize = 1024;
+const DESCRIPTION_LIMIT: usize = 4096;
+
+pub const COLOR_SLEEPER: u32 = 0x1d2b3d;
+pub const COLOR_ESPN: u32 = 0xcc0000;
+pub const COLOR_GOOD: u32 = 0x2ecc71;
+pub const COLOR_WARN: u32 = 0xf1c40f;
+pub const COLOR_BAD: u32 = 0xe74c3c;
+pub const COLOR_NEUTRAL: u32 = 0x3498db;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct EmbedField {
+    pub name: String,
+    pub value: String,
+    pub inline: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Embed {
+    pub title: String,
+    pub description: Option<String>,
+    pub color: u32,
+    pub thumbnail: Option<String>,
+    pub fields: Vec<EmbedField>,
+    pub footer: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Reply {
+    Embeds(Vec<Embed>),
+    Text(String),
+}
+
+impl Embed {
+    pub fn new(title: String, color: u32) -> Embed {
+        Embed {
+            title,
+            description: None,
+            color,
+            thumbnail: None,
+            fields: vec![],
+            footer: None,
+        }
+    }
+
+    pub fn field(mut self, name: String, value: String, inline: bool) -> Embed {
+        self.fields.push(EmbedField {
+            name,
+            value,
+            inline,
+        });
+        self
+    }
+
+    fn char_count(&self) -> usize {
+        self.title.chars().count()
+            + self.description.as_ref().map_or(0, |d| d.chars().count())
+            + self.footer.as_ref().map_or(0, |f| f.chars().count())
+            + self
+                .fields
+                .iter()
+                .map(|f| f.name.chars().count() + f.value.chars().count())
+                .sum::<usize>()
+    }
+
+    fn fits(&self) -> bool {
+        self.title.chars().count() <= EMBED_TITLE_LIMIT
+            && self
+                .description
+                .as_ref()
+                .is_none_or(|d| d.chars().count() <= DESCRIPTION_LIMIT)
+            && self.fields.len() <= EMBED_FIELD_LIMIT
+            && self.fields.iter().all(|f| {
+                !f.value.is_empty()
+                    && f.name.chars().count() <= FIELD_NAME_LIMIT
+                    && f.value.chars().count() <= FIELD_VALUE_LIMIT
+            })
+            && self.char_count() <= EMBED_TOTAL_LIMIT
+    }
+}
+
+// Splits embeds into as many messages as it takes to stay under Discord's limits.
+pub fn paginate(embeds: Vec<Embed>) -> Vec<Reply> {
+    let mut pages: Vec<Reply> = vec![];
+    let mut current: Vec<Embed> = vec![];
+    let mut current_chars = 0;
+    for embed in embeds.into_iter().flat_map(split_embed) {
+        let chars = embed.char_count();
+        if !current.is_empty()
+            && (current.len() == EMBEDS_PER_MESSAGE || current_chars + chars > EMBED_TOTAL_LIMIT)
+        {
+            pages.push(Reply::Embeds(std::mem::take(&mut current)));
+            current_chars = 0;
+        }
+        current_chars += chars;
+        current.push(embed);
+    }
+    if !current.is_empty() {
+        pages.push(Reply::Embeds(current));
+    }
+    pages
+}
+
+// Breaks an embed with too many or too long fields into several embeds with the same title.
+fn split_embed(mut embed: Embed) -> Vec<Embed> {
+    embed.title = truncate(&embed.title, EMBED_TITLE_LIMIT);
+    embed.description = embed.description.map(|d| truncate(&d, DESCRIPTION_LIMIT));
+    for f in embed.fields.iter_mut() {
+        f.name = truncate(&f.name, FIELD_NAME_LIMIT);
+        f.value = if f.value.is_empty() {
+            "-".to_string()
+        } else {
+            truncate(&f.value, FIELD_VALUE_LIMIT)
+        };
+    }
+    if embed.fits() {
+        return vec![embed];
+    }
+
+    let fields = std::mem::take(&mut embed.fields);
+    let mut ret: Vec<Embed> = vec![];
+    let mut page = embed.clone();
+    for field in fields {
+        page.fields.push(field);
+        if !page.fits() && page.fields.len() > 1 {
+            let field = page.fields.pop().unwrap();
+            ret.push(page);
+            page = embed.clone();
+            page.description = None;
+            page.fields.push(field);
+        }
+    }
+    ret.push(page);
+    ret
+}
+
+// Splits text on line boundaries into chunks that each fit in a message of `max` characters.
+pub fn split_message(content: &str, max: usize) -> Vec<String> {
+    let mut chunks: Vec<String> = vec![];
+    let mut current = String::new();
+    for line in content.lines() {
+        let line = truncate(line, max);
+        if !current.is_empty() && current.chars().count() + line.chars().count() + 1 > max {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(&line);
+    }
+    if !current.is_empty() || chunks.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+// Splits text on line boundaries into code blocks that each fit in a message.
+pub fn paginate_text(content: &str) -> Vec<Reply> {
+    let max = MESSAGE_LIMIT - "```\n\n```".len();
+    split_message(content, max)
+        .iter()
+        .map(|chunk| text_block(chunk))
+        .collect()
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() > max {
+        let truncated: String = s.chars().take(max - 1).collect();
+        format!("{}…", truncated)
+    } else {
+        s.to_string()
+    }
+}
+
+// Wraps content in a code block, truncating so it fits in a single message.
+pub fn text_block(content: &str) -> Reply {
+    let max = MESSAGE_LIMIT - "```\n\n```".len();
+    Reply::Text(format!("```\n{}\n```", truncate(content, max)))
+}
+
+pub fn league_color(league_type: &LeagueType) -> u32 {
+    match league_type {
+        LeagueType::SLEEPER => COLOR_SLEEPER,
+        LeagueType::ESPN => COLOR_ESPN,
+    }
+}
+
+fn format_score(score: Option<f64>) -> String {
+    score.map_or("-".to_string(), |s| format!("{:.2}", s))
+}
+
+fn player_line(player: &FantasyPlayer) -> String {
+    let team = player
+        .nfl_team
+        .as_ref()
+        .map_or("".to_string(), |t| format!(" ({})", t));
+    let status = match player.injury_status.as_deref() {
+        None | Some("ACTIVE") | Some("NORMAL") => "".to_string(),
+        Some(s) => format!(" · **{}**", s),
+    };
+    format!("`{:<3}` {}{}{}", player.position, player.name, team, status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn long_text_splits_on_lines() {
+        let lines: Vec<String> = (0..300).map(|i| format!("player number {}", i)).collect();
+        let pages = paginate_text(&lines.join("\n"));
+        assert!(pages.len() > 1);
+        for page in pages.iter() {
+            match page {
+                Reply::Text(t) => assert!(t.chars().count() <= MESSAGE_LIMIT),
+                Reply::Embeds(_) => panic!("expected text"),
+            }
+        }
+        match &pages[1] {
+            Reply::Text(t) => assert!(t.starts_with("```\nplayer number ")),
+            Reply::Embeds(_) => panic!("expected text"),
+        }
+    }
+}
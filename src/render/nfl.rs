@@ -0,0 +1,31 @@
+use super::super::nfl::{GameStatus, NflGame};
+use super::{paginate, text_block, Embed, Reply, COLOR_NEUTRAL};
+
+pub fn nfl_scores(week_num: u32, games: &[NflGame]) -> Vec<Reply> {
+    if games.is_empty() {
+        return vec![text_block(&format!("No NFL games in week {}.", week_num))];
+    }
+    let mut embed = Embed::new(format!("NFL week {}", week_num), COLOR_NEUTRAL);
+    for game in games.iter() {
+        let score = |s: Option<u32>| s.map_or("".to_string(), |s| format!(" {}", s));
+        let name = format!(
+            "{}{} @ {}{}",
+            game.away_team,
+            score(game.away_score),
+            game.home_team,
+            score(game.home_score)
+        );
+        let value = match game.status {
+            // Discord shows these in the reader's own timezone
+            GameStatus::Scheduled => format!("<t:{}:f>", game.kickoff.timestamp()),
+            GameStatus::InProgress => match (game.quarter, &game.display_clock) {
+                (Some(q), Some(c)) if q > 4 => format!("🔴 OT {}", c),
+                (Some(q), Some(c)) => format!("🔴 Q{} {}", q, c),
+                _ => "🔴 In progress".to_string(),
+            },
+            GameStatus::Final => "Final".to_string(),
+        };
+        embed = embed.field(name, value, true);
+    }
+    paginate(vec![embed])
+}
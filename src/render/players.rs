@@ -0,0 +1,217 @@
+use super::super::fantasy_client::{FaabBalance, FantasyPlayer, PlayerProjection};
+use super::super::lineup::is_out;
+use super::super::nfl::GameStatus;
+use super::super::players::LeaguePlayer;
+use super::super::startsit::{self, PlayerOutlook, Recommendation};
+use super::super::trending::{Availability, TrendingPlayer};
+use super::{
+    paginate, player_line, truncate, Embed, Reply, COLOR_BAD, COLOR_GOOD, COLOR_NEUTRAL,
+    COLOR_WARN, DESCRIPTION_LIMIT,
+};
+
+pub fn startsit(week_num: u32, outlooks: &[PlayerOutlook], missing: &[String]) -> Vec<Reply> {
+    let name = |i: usize| outlooks[i].projection.player.name.clone();
+    let description = match startsit::recommend(outlooks) {
+        Some(Recommendation::Start(i)) => format!("👉 Start **{}**", name(i)),
+        Some(Recommendation::TossUp(i)) => {
+            format!("🪙 Toss-up, but lean **{}** on recent form", name(i))
+        }
+        Some(Recommendation::Locked) => "🔒 Their games have already started.".to_string(),
+        None => "Couldn't find any of those players.".to_string(),
+    };
+    let mut embed = Embed::new(format!("Start/sit, week {}", week_num), COLOR_NEUTRAL);
+    embed.description = Some(description);
+    for outlook in outlooks.iter() {
+        let projection = &outlook.projection;
+        let mut lines = vec![format!(
+            "Projected: {}",
+            projection
+                .projected
+                .map_or("-".to_string(), |p| format!("{:.2}", p))
+        )];
+        lines.push(match (outlook.opponent(), &outlook.game) {
+            (Some(opponent), Some(game)) => match game.status {
+                GameStatus::Scheduled => {
+                    format!("{} · <t:{}:f>", opponent, game.kickoff.timestamp())
+                }
+                GameStatus::InProgress => format!(
+                    "{} · 🔴 in progress, {:.0}% left",
+                    opponent,
+                    game.fraction_remaining() * 100.0
+                ),
+                GameStatus::Final => format!("{} · final", opponent),
+            },
+            _ => "Bye".to_string(),
+        });
+        if outlook.locked {
+            lines.push("🔒 Locked".to_string());
+        }
+        if let Some(status) = &projection.player.injury_status {
+            lines.push(format!("Status: **{}**", status));
+        }
+        if let Some(average) = projection.recent_average() {
+            let recent: Vec<String> = projection
+                .recent
+                .iter()
+                .map(|p| format!("{:.1}", p))
+                .collect();
+            let trend = match (projection.recent.first(), projection.recent.last()) {
+                (Some(first), Some(last)) if last > first => " 📈",
+                (Some(first), Some(last)) if last < first => " 📉",
+                _ => "",
+            };
+            lines.push(format!(
+                "Recent: {} (avg {:.1}){}",
+                recent.join(", "),
+                average,
+                trend
+            ));
+        }
+        embed = embed.field(player_line(&projection.player), lines.join("\n"), true);
+    }
+    if !missing.is_empty() {
+        embed.footer = Some(format!("Couldn't find: {}", missing.join(", ")));
+    }
+    paginate(vec![embed])
+}
+
+pub fn waivers(
+    league_name: &str,
+    color: u32,
+    position: Option<&str>,
+    ranked: &[(PlayerProjection, f64)],
+    faab: &[FaabBalance],
+) -> Vec<Reply> {
+    let title = match position {
+        Some(p) => format!("{} waiver wire: {}", league_name, p),
+        None => format!("{} waiver wire", league_name),
+    };
+    let mut embed = Embed::new(title, color);
+    embed.description = Some(if ranked.is_empty() {
+        "Nobody worth picking up.".to_string()
+    } else {
+        let lines: Vec<String> = ranked
+            .iter()
+            .enumerate()
+            .map(|(i, (p, value))| {
+                let recent = p
+                    .recent_average()
+                    .map_or("-".to_string(), |r| format!("{:.1}", r));
+                let ros = p
+                    .rest_of_season
+                    .map_or("-".to_string(), |r| format!("{:.1}", r));
+                format!(
+                    "{}. {}\n{:.1}/wk · recent avg {} · rest of season {}",
+                    i + 1,
+                    player_line(&p.player),
+                    value,
+                    recent,
+                    ros
+                )
+            })
+            .collect();
+        truncate(&lines.join("\n"), DESCRIPTION_LIMIT)
+    });
+    let mut embeds = vec![embed];
+
+    if !faab.is_empty() {
+        let mut faab: Vec<&FaabBalance> = faab.iter().collect();
+        faab.sort_by_key(|f| std::cmp::Reverse(f.remaining));
+        let lines: Vec<String> = faab
+            .iter()
+            .map(|f| format!("${} / ${} · {}", f.remaining, f.budget, f.team.team_name))
+            .collect();
+        let mut faab_embed = Embed::new("FAAB remaining".to_string(), color);
+        faab_embed.description = Some(truncate(&lines.join("\n"), DESCRIPTION_LIMIT));
+        embeds.push(faab_embed);
+    }
+    paginate(embeds)
+}
+
+pub fn trending(
+    league_name: &str,
+    kind: &str,
+    hours: u32,
+    players: &[TrendingPlayer],
+) -> Vec<Reply> {
+    let (title, color) = match kind {
+        "drop" => ("Most dropped", COLOR_BAD),
+        _ => ("Most added", COLOR_GOOD),
+    };
+    let mut embed = Embed::new(format!("{}, last {} hours", title, hours), color);
+    embed.description = Some(if players.is_empty() {
+        "Nobody, apparently.".to_string()
+    } else {
+        let lines: Vec<String> = players
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                let availability = match &t.availability {
+                    Availability::Rostered(team) => format!("🔒 {}", team.team_name),
+                    Availability::Available => "✅ available".to_string(),
+                    Availability::Unknown => "❔ availability unknown".to_string(),
+                };
+                format!(
+                    "{}. {}\n{} {} · {}",
+                    i + 1,
+                    player_line(&t.player),
+                    t.count,
+                    if kind == "drop" { "drops" } else { "adds" },
+                    availability
+                )
+            })
+            .collect();
+        truncate(&lines.join("\n"), DESCRIPTION_LIMIT)
+    });
+    embed.footer = Some(format!("Availability in {}", league_name));
+    paginate(vec![embed])
+}
+
+pub fn player_card(
+    player: &FantasyPlayer,
+    injury_start_date: Option<String>,
+    image_url: &str,
+    leagues: &[LeaguePlayer],
+) -> Vec<Reply> {
+    let color = if is_out(player) {
+        COLOR_BAD
+    } else if player.injury_status.is_some() {
+        COLOR_WARN
+    } else {
+        COLOR_NEUTRAL
+    };
+    let mut lines = vec![format!(
+        "{} · {}",
+        player.position,
+        player.nfl_team.as_deref().unwrap_or("Free agent")
+    )];
+    if let Some(status) = &player.injury_status {
+        lines.push(match injury_start_date {
+            Some(d) => format!("**{}** since {}", status, d),
+            None => format!("**{}**", status),
+        });
+    }
+    let mut embed = Embed::new(player.name.clone(), color);
+    embed.description = Some(lines.join("\n"));
+    embed.thumbnail = Some(image_url.to_string());
+
+    for league in leagues.iter() {
+        let mut value = vec![match &league.rostered_by {
+            Some(team) => format!("🔒 {}", team.team_name),
+            None => "✅ Available".to_string(),
+        }];
+        if let Some(p) = &league.projection {
+            value.push(format!(
+                "Season: {}",
+                p.season_points
+                    .map_or("-".to_string(), |s| format!("{:.2}", s))
+            ));
+            if !p.recent.is_empty() {
+                let recent: Vec<String> = p.recent.iter().map(|r| format!("{:.2}", r)).collect();
+                value.push(format!("Last {}: {}", p.recent.len(), recent.join(", ")));
+            }
+        }
+        embed = embed.field(league.league_name.clone(), value.join("\n"), true);
+    }
+    paginate(vec![embed])
+}
@@ -0,0 +1,255 @@
+use super::super::fantasy_client::{
+    Bracket, BracketGame, BracketKind, BracketSource, BracketTeam, FantasyMatchup,
+};
+use super::super::playoffs::PlayoffOdds;
+use super::league::matchup_embed;
+use super::{format_score, paginate, paginate_text, text_block, truncate, Embed, Reply};
+
+// "Semifinals", "3rd place game", "Consolation round 1" and so on.
+fn round_name(bracket: &Bracket, game: &BracketGame) -> String {
+    match bracket.kind {
+        BracketKind::Winners => {}
+        BracketKind::Consolation => return format!("Consolation round {}", game.round),
+        BracketKind::Losers => return format!("Losers round {}", game.round),
+    }
+    match game.place {
+        Some(1) => "Championship".to_string(),
+        Some(2) => "2nd place game".to_string(),
+        Some(3) => "3rd place game".to_string(),
+        Some(p) => format!("{}th place game", p),
+        None => match bracket.rounds() - game.round {
+            0 => "Final".to_string(),
+            1 => "Semifinals".to_string(),
+            2 => "Quarterfinals".to_string(),
+            _ => format!("Round {}", game.round),
+        },
+    }
+}
+
+fn bracket_weeks(game: &BracketGame) -> String {
+    match game.week_nums.as_slice() {
+        [] => String::new(),
+        [week] => format!("week {}", week),
+        [first, .., last] => format!("weeks {}-{}", first, last),
+    }
+}
+
+// The playoff games being played now, labeled with their round.
+pub fn playoff_matchups(brackets: &[Bracket]) -> Vec<Reply> {
+    let mut embeds = vec![];
+    for bracket in brackets.iter() {
+        for game in bracket.current_games() {
+            if let (Some(team1), Some(team2)) = (&game.team1.team, &game.team2.team) {
+                let mut embed = matchup_embed(&FantasyMatchup {
+                    team1: team1.clone(),
+                    team2: team2.clone(),
+                    score1: game.team1.score,
+                    score2: game.team2.score,
+                    week_num: game.week_nums.first().copied().unwrap_or(0),
+                });
+                embed.description = Some(format!(
+                    "{} · {}",
+                    round_name(bracket, game),
+                    bracket_weeks(game)
+                ));
+                embeds.push(embed);
+            }
+        }
+    }
+    if embeds.is_empty() {
+        return vec![text_block("No playoff games right now.")];
+    }
+    paginate(embeds)
+}
+
+const BRACKET_NAME_WIDTH: usize = 24;
+
+fn bracket_line(side: &BracketTeam, winner: &Option<String>) -> String {
+    let name = match (&side.team, &side.from) {
+        (Some(team), _) => team.team_name.clone(),
+        (None, Some(BracketSource::WinnerOf(id))) => format!("Winner of game {}", id),
+        (None, Some(BracketSource::LoserOf(id))) => format!("Loser of game {}", id),
+        (None, None) => "TBD".to_string(),
+    };
+    let name = match side.seed {
+        Some(seed) => format!("({}) {}", seed, name),
+        None => name,
+    };
+    let won = side
+        .team
+        .as_ref()
+        .is_some_and(|t| winner.as_ref() == Some(&t.id));
+    format!(
+        "{:<width$} {:>7}{}",
+        truncate(&name, BRACKET_NAME_WIDTH),
+        format_score(side.score),
+        if won { " W" } else { "" },
+        width = BRACKET_NAME_WIDTH
+    )
+}
+
+// Every round of the playoffs as text, winners bracket first.
+pub fn bracket(league_name: &str, brackets: &[Bracket]) -> Vec<Reply> {
+    if brackets.iter().all(|b| b.games.is_empty()) {
+        return vec![text_block("The playoff bracket hasn't been set yet.")];
+    }
+    let mut lines = vec![format!("{} playoffs", league_name)];
+    for bracket in brackets.iter() {
+        lines.push(String::new());
+        lines.push(
+            match bracket.kind {
+                BracketKind::Winners => "WINNERS BRACKET",
+                BracketKind::Consolation => "CONSOLATION BRACKET",
+                BracketKind::Losers => "LOSERS BRACKET",
+            }
+            .to_string(),
+        );
+        let mut games: Vec<&BracketGame> = bracket.games.iter().collect();
+        games.sort_by_key(|g| (g.round, g.place.is_some(), g.id));
+        for game in games {
+            lines.push(String::new());
+            lines.push(format!(
+                "Game {} · {} · {}",
+                game.id,
+                round_name(bracket, game),
+                bracket_weeks(game)
+            ));
+            lines.push(format!("  {}", bracket_line(&game.team1, &game.winner)));
+            lines.push(format!("  {}", bracket_line(&game.team2, &game.winner)));
+        }
+    }
+    paginate_text(&lines.join("\n"))
+}
+
+fn percent(chance: f64) -> String {
+    match chance {
+        c if c >= 1.0 => "✅ 100%".to_string(),
+        c if c <= 0.0 => "❌ 0%".to_string(),
+        c if c < 0.001 => "<0.1%".to_string(),
+        c if c > 0.999 => ">99.9%".to_string(),
+        c => format!("{:.1}%", c * 100.0),
+    }
+}
+
+pub fn playoffs_embed(
+    league_name: &str,
+    color: u32,
+    has_byes: bool,
+    simulations: u32,
+    odds: &[PlayoffOdds],
+) -> Embed {
+    let mut embed = Embed::new(format!("{} playoff odds", league_name), color);
+    for (i, o) in odds.iter().enumerate() {
+        let bye = if has_byes {
+            format!(" · bye {}", percent(o.bye))
+        } else {
+            "".to_string()
+        };
+        embed = embed.field(
+            format!("{}. {}", i + 1, o.team.team_name),
+            format!(
+                "playoffs **{}**{} · title {}",
+                percent(o.playoffs),
+                bye,
+                percent(o.title)
+            ),
+            false,
+        );
+    }
+    embed.footer = Some(format!(
+        "From {} simulations of the rest of the season",
+        simulations
+    ));
+    embed
+}
+
+pub fn playoffs(
+    league_name: &str,
+    color: u32,
+    has_byes: bool,
+    simulations: u32,
+    odds: &[PlayoffOdds],
+) -> Vec<Reply> {
+    paginate(vec![playoffs_embed(
+        league_name,
+        color,
+        has_byes,
+        simulations,
+        odds,
+    )])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::FantasyTeam;
+
+    fn bracket_team(team: Option<FantasyTeam>, from: Option<BracketSource>) -> BracketTeam {
+        BracketTeam {
+            team,
+            seed: None,
+            from,
+            score: None,
+        }
+    }
+
+    #[test]
+    fn bracket_rounds_and_sources() {
+        let semi = BracketGame {
+            id: 1,
+            round: 1,
+            week_nums: vec![15],
+            team1: BracketTeam {
+                score: Some(120.5),
+                ..bracket_team(Some(FantasyTeam::test("1")), None)
+            },
+            team2: BracketTeam {
+                score: Some(99.0),
+                ..bracket_team(Some(FantasyTeam::test("4")), None)
+            },
+            winner: Some("1".to_string()),
+            place: None,
+        };
+        let bracket = Bracket {
+            kind: BracketKind::Winners,
+            games: vec![
+                semi,
+                BracketGame {
+                    id: 3,
+                    round: 2,
+                    week_nums: vec![16, 17],
+                    team1: bracket_team(None, Some(BracketSource::WinnerOf(1))),
+                    team2: bracket_team(None, Some(BracketSource::WinnerOf(2))),
+                    winner: None,
+                    place: Some(1),
+                },
+            ],
+        };
+        assert_eq!(round_name(&bracket, &bracket.games[0]), "Semifinals");
+        assert_eq!(bracket_weeks(&bracket.games[1]), "weeks 16-17");
+
+        let text = match &super::bracket("Test League", std::slice::from_ref(&bracket))[0] {
+            Reply::Text(t) => t.clone(),
+            Reply::Embeds(_) => panic!("expected text"),
+        };
+        assert!(text.contains("Game 1 · Semifinals · week 15"));
+        assert!(text.contains("Team 1                    120.50 W"));
+        assert!(text.contains("Game 3 · Championship · weeks 16-17"));
+        assert!(text.contains("Winner of game 2"));
+
+        // decided games and ones without both teams set aren't current
+        assert!(matches!(
+            &playoff_matchups(std::slice::from_ref(&bracket))[0],
+            Reply::Text(_)
+        ));
+        let mut bracket = bracket;
+        bracket.games[0].winner = None;
+        match &playoff_matchups(&[bracket])[0] {
+            Reply::Embeds(e) => {
+                assert_eq!(e.len(), 1);
+                assert_eq!(e[0].description.as_deref(), Some("Semifinals · week 15"));
+            }
+            Reply::Text(_) => panic!("expected embeds"),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use super::super::preview::{MatchupPreview, TeamPreview};
+use super::{paginate, text_block, Embed, Reply};
+
+pub fn preview_embed(preview: &MatchupPreview, color: u32) -> Embed {
+    let team_value = |t: &TeamPreview, chance: Option<f64>| {
+        let mut lines = vec![t.team.owner_name.clone()];
+        if let Some(r) = &t.record {
+            lines.push(format!("Record: {}", r));
+        }
+        lines.push(format!(
+            "Projected: {}",
+            t.projected.map_or("-".to_string(), |p| format!("{:.2}", p))
+        ));
+        if let Some(c) = chance {
+            lines.push(format!("Win chance: **{:.0}%**", c * 100.0));
+        }
+        lines.join("\n")
+    };
+    let (wins1, wins2, ties) = preview.head_to_head;
+    let h2h = if wins1 + wins2 + ties == 0 {
+        "First meeting".to_string()
+    } else if wins1 == wins2 {
+        format!("Series tied {}-{}", wins1, wins2)
+    } else if wins1 > wins2 {
+        format!("{} leads {}-{}", preview.team1.team.team_name, wins1, wins2)
+    } else {
+        format!("{} leads {}-{}", preview.team2.team.team_name, wins2, wins1)
+    };
+
+    let mut embed = Embed::new(
+        format!(
+            "{} vs. {}",
+            preview.team1.team.team_name, preview.team2.team.team_name
+        ),
+        color,
+    )
+    .field(
+        preview.team1.team.team_name.clone(),
+        team_value(&preview.team1, preview.win_probability),
+        true,
+    )
+    .field(
+        preview.team2.team.team_name.clone(),
+        team_value(&preview.team2, preview.win_probability.map(|p| 1.0 - p)),
+        true,
+    );
+    embed.description = Some(h2h);
+    let favorite = match preview.win_probability {
+        Some(p) if p < 0.5 => &preview.team2,
+        _ => &preview.team1,
+    };
+    embed.thumbnail = favorite.team.avatar_url.clone();
+    embed
+}
+
+pub fn previews(
+    league_name: &str,
+    color: u32,
+    week_num: u32,
+    previews: &[MatchupPreview],
+) -> Vec<Reply> {
+    if previews.is_empty() {
+        return vec![text_block(&format!("No matchups for week {}.", week_num))];
+    }
+    let mut embeds: Vec<Embed> = previews.iter().map(|p| preview_embed(p, color)).collect();
+    embeds[0].description = Some(format!(
+        "**{} week {} preview**\n{}",
+        league_name,
+        week_num,
+        embeds[0].description.clone().unwrap_or_default()
+    ));
+    paginate(embeds)
+}
@@ -0,0 +1,125 @@
+use super::super::fantasy_client::{PastMatchup, PastTeam};
+use super::super::records::{NewRecord, RecordBook, Streak, TeamWeek};
+use super::{paginate, truncate, Embed, Reply, FIELD_VALUE_LIMIT};
+
+// "Team Name (owner)"
+fn past_team(team: &PastTeam) -> String {
+    format!("{} ({})", team.team_name, team.owner_name)
+}
+
+fn team_week_line(w: &TeamWeek) -> String {
+    format!(
+        "{} · **{:.2}** · {} week {}",
+        past_team(&w.team),
+        w.score,
+        w.season,
+        w.week_num
+    )
+}
+
+fn margin_line(m: &PastMatchup) -> String {
+    format!(
+        "{} over {} by **{:.2}** · {} week {}",
+        past_team(&m.team1),
+        m.team2.team_name,
+        m.score1 - m.score2,
+        m.season,
+        m.week_num
+    )
+}
+
+fn streak_line(s: &Streak) -> String {
+    format!(
+        "{} · **{}** games from {} week {}",
+        past_team(&s.team),
+        s.length,
+        s.start_season,
+        s.start_week
+    )
+}
+
+pub fn records(league_name: &str, color: u32, book: &RecordBook) -> Vec<Reply> {
+    let or_none = |line: Option<String>| line.unwrap_or_else(|| "-".to_string());
+    let mut embed = Embed::new(format!("{} record book", league_name), color)
+        .field(
+            "Highest score".to_string(),
+            or_none(book.high_score.as_ref().map(team_week_line)),
+            false,
+        )
+        .field(
+            "Lowest score".to_string(),
+            or_none(book.low_score.as_ref().map(team_week_line)),
+            false,
+        )
+        .field(
+            "Biggest blowout".to_string(),
+            or_none(book.biggest_margin.as_ref().map(margin_line)),
+            false,
+        )
+        .field(
+            "Longest win streak".to_string(),
+            or_none(book.win_streak.as_ref().map(streak_line)),
+            false,
+        )
+        .field(
+            "Longest losing streak".to_string(),
+            or_none(book.loss_streak.as_ref().map(streak_line)),
+            false,
+        )
+        .field(
+            "Most points in a season".to_string(),
+            or_none(book.season_points.as_ref().map(|s| {
+                format!(
+                    "{} · **{:.2}** in {}",
+                    past_team(&s.team),
+                    s.points,
+                    s.season
+                )
+            })),
+            false,
+        );
+    let champions: Vec<String> = book
+        .champions
+        .iter()
+        .rev()
+        .map(|c| format!("{}: 🏆 {}", c.season, past_team(&c.team)))
+        .collect();
+    embed = embed.field(
+        "Champions".to_string(),
+        if champions.is_empty() {
+            "None yet".to_string()
+        } else {
+            truncate(&champions.join("\n"), FIELD_VALUE_LIMIT)
+        },
+        false,
+    );
+    if let Some((season, week)) = &book.through {
+        embed.footer = Some(format!("Through {} week {}", season, week));
+    }
+    paginate(vec![embed])
+}
+
+// Lines for the weekly recap about records that just fell.
+pub fn new_records(records: &[NewRecord]) -> String {
+    let lines: Vec<String> = records
+        .iter()
+        .map(|r| match r {
+            NewRecord::HighScore(w) => format!("📈 Highest score ever: {}", team_week_line(w)),
+            NewRecord::LowScore(w) => format!("📉 Lowest score ever: {}", team_week_line(w)),
+            NewRecord::Margin(m) => format!("💥 Biggest blowout ever: {}", margin_line(m)),
+            NewRecord::WinStreak(s) => format!("🔥 Longest win streak ever: {}", streak_line(s)),
+            NewRecord::LossStreak(s) => {
+                format!("🥶 Longest losing streak ever: {}", streak_line(s))
+            }
+            NewRecord::SeasonPoints(s) => format!(
+                "💯 Most points in a season: {} with {:.2} and counting",
+                past_team(&s.team),
+                s.points
+            ),
+            NewRecord::Champion(c) => {
+                format!("🏆 {} champion: {}", c.season, past_team(&c.team))
+            }
+        })
+        .collect();
+    format!("__New league records__\n{}", lines.join("\n"))
+}
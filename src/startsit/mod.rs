@@ -0,0 +1,184 @@
+use super::fantasy_client::PlayerProjection;
+use super::lineup::is_out;
+use super::nfl::{self, NflGame};
+use chrono::{DateTime, Utc};
+
+// projections closer than this are too close to call without looking at recent form
+const TOSS_UP_MARGIN: f64 = 1.0;
+
+pub struct PlayerOutlook {
+    pub projection: PlayerProjection,
+    // None if the player's team is on bye, or they don't have one
+    pub game: Option<NflGame>,
+    // their game has started, so it's too late to start or sit them
+    pub locked: bool,
+}
+
+impl PlayerOutlook {
+    pub fn new(
+        projection: PlayerProjection,
+        games: &[NflGame],
+        now: DateTime<Utc>,
+    ) -> PlayerOutlook {
+        let team = projection.player.nfl_team.as_deref();
+        let game = team.and_then(|t| nfl::game_for_team(games, t)).cloned();
+        let locked = nfl::is_locked(games, team, now);
+        PlayerOutlook {
+            projection,
+            game,
+            locked,
+        }
+    }
+
+    // e.g. "vs BUF" at home or "@ BUF" on the road
+    pub fn opponent(&self) -> Option<String> {
+        let team = self.projection.player.nfl_team.as_deref()?;
+        let game = self.game.as_ref()?;
+        if game.home_team == team {
+            Some(format!("vs {}", game.away_team))
+        } else {
+            Some(format!("@ {}", game.home_team))
+        }
+    }
+
+    // What we actually expect them to score, which is nothing if they aren't playing.
+    pub fn expected(&self) -> f64 {
+        if self.game.is_none() || is_out(&self.projection.player) {
+            0.0
+        } else {
+            self.projection.projected.unwrap_or(0.0)
+        }
+    }
+}
+
+pub enum Recommendation {
+    // index of the player to start
+    Start(usize),
+    // the projections are about even, so this one gets the nod on recent form
+    TossUp(usize),
+    // every one of their games has started
+    Locked,
+}
+
+// Picks between the players whose games haven't started yet.
+pub fn recommend(outlooks: &[PlayerOutlook]) -> Option<Recommendation> {
+    if outlooks.is_empty() {
+        return None;
+    }
+    let mut order: Vec<usize> = (0..outlooks.len())
+        .filter(|i| !outlooks[*i].locked)
+        .collect();
+    if order.is_empty() {
+        return Some(Recommendation::Locked);
+    }
+    order.sort_by(|a, b| {
+        outlooks[*b]
+            .expected()
+            .partial_cmp(&outlooks[*a].expected())
+            .unwrap()
+    });
+    let best = *order.first()?;
+    let runner_up = match order.get(1) {
+        Some(r) => *r,
+        None => return Some(Recommendation::Start(best)),
+    };
+    if outlooks[best].expected() - outlooks[runner_up].expected() >= TOSS_UP_MARGIN {
+        return Some(Recommendation::Start(best));
+    }
+    let form = |i: usize| outlooks[i].projection.recent_average().unwrap_or(0.0);
+    if form(runner_up) > form(best) {
+        Some(Recommendation::TossUp(runner_up))
+    } else {
+        Some(Recommendation::TossUp(best))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::FantasyPlayer;
+    use crate::nfl::GameStatus;
+    use chrono::Utc;
+
+    fn outlook(
+        team: &str,
+        status: Option<&str>,
+        projected: f64,
+        recent: Vec<f64>,
+    ) -> PlayerOutlook {
+        outlook_at(team, status, projected, recent, GameStatus::Scheduled)
+    }
+
+    fn outlook_at(
+        team: &str,
+        status: Option<&str>,
+        projected: f64,
+        recent: Vec<f64>,
+        game_status: GameStatus,
+    ) -> PlayerOutlook {
+        let now = Utc::now();
+        let games = vec![NflGame {
+            id: "1".to_string(),
+            home_team: "KC".to_string(),
+            away_team: "BUF".to_string(),
+            kickoff: now + chrono::Duration::hours(1),
+            status: game_status,
+            quarter: None,
+            clock_secs: None,
+            display_clock: None,
+            home_score: None,
+            away_score: None,
+        }];
+        let projection = PlayerProjection {
+            player: FantasyPlayer {
+                id: team.to_string(),
+                name: team.to_string(),
+                position: "WR".to_string(),
+                nfl_team: Some(team.to_string()),
+                injury_status: status.map(|s| s.to_string()),
+            },
+            projected: Some(projected),
+            recent,
+            rest_of_season: None,
+            season_points: None,
+        };
+        PlayerOutlook::new(projection, &games, now)
+    }
+
+    #[test]
+    fn recommends_highest_expected_then_recent_form() {
+        let outlooks = vec![
+            outlook("KC", None, 12.0, vec![10.0]),
+            outlook("BUF", Some("Out"), 20.0, vec![25.0]),
+            outlook("DET", None, 30.0, vec![]),
+        ];
+        assert_eq!(outlooks[0].opponent().as_deref(), Some("vs BUF"));
+        assert_eq!(outlooks[1].opponent().as_deref(), Some("@ KC"));
+        assert!(outlooks[2].opponent().is_none());
+        assert!(matches!(
+            recommend(&outlooks),
+            Some(Recommendation::Start(0))
+        ));
+
+        let close = vec![
+            outlook("KC", None, 12.0, vec![8.0, 9.0]),
+            outlook("BUF", None, 11.5, vec![15.0, 20.0]),
+        ];
+        assert!(matches!(recommend(&close), Some(Recommendation::TossUp(1))));
+
+        // it's too late to sit somebody whose game has started
+        let started = vec![
+            outlook_at("KC", None, 30.0, vec![], GameStatus::InProgress),
+            outlook("DET", None, 5.0, vec![]),
+        ];
+        assert!(started[0].locked && !started[1].locked);
+        assert!(matches!(
+            recommend(&started),
+            Some(Recommendation::Start(1))
+        ));
+        assert!(matches!(
+            recommend(&started[..1]),
+            Some(Recommendation::Locked)
+        ));
+    }
+}
@@ -0,0 +1,52 @@
+use log::debug;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+use std::path::PathBuf;
+
+pub const DATA_DIR: &str = "data";
+
+// A tiny JSON document store on the local filesystem, one file per key.
+pub struct Storage {
+    dir: PathBuf,
+}
+
+impl Storage {
+    pub fn new(dir: &str) -> Storage {
+        Storage {
+            dir: PathBuf::from(dir),
+        }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    pub fn load<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let file = File::open(self.path(key)).ok()?;
+        match serde_json::from_reader(BufReader::new(file)) {
+            Ok(v) => Some(v),
+            Err(e) => {
+                eprintln!("Could not parse stored {}. Err:\n{}", key, e);
+                None
+            }
+        }
+    }
+
+    pub fn save<T: Serialize>(&self, key: &str, value: &T) -> Result<(), Box<dyn Error>> {
+        let path = self.path(key);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        debug!("saving {:?}", path);
+        let file = File::create(path)?;
+        serde_json::to_writer(BufWriter::new(file), value)?;
+        Ok(())
+    }
+}
+
+pub fn league_key(league_id: &str, name: &str) -> String {
+    format!("leagues/{}/{}", league_id, name)
+}
@@ -0,0 +1,132 @@
+use super::fantasy_client::sleeper::{SleeperPlayers, SleeperTrendingPlayer};
+use super::fantasy_client::{FantasyPlayer, FantasyRoster, FantasyTeam, LeagueType};
+use super::players;
+
+pub const DEFAULT_LOOKBACK_HOURS: u32 = 24;
+pub const TRENDING_LIMIT: u32 = 15;
+
+pub enum Availability {
+    Available,
+    Rostered(FantasyTeam),
+    // an ESPN league, and Sleeper doesn't know the player's ESPN ID
+    Unknown,
+}
+
+pub struct TrendingPlayer {
+    pub player: FantasyPlayer,
+    pub count: u32,
+    pub availability: Availability,
+}
+
+// Fills in Sleeper's trending player IDs, checking who has each of them in a league.
+pub fn build(
+    trending: &[SleeperTrendingPlayer],
+    sleeper_players: &SleeperPlayers,
+    league_type: &LeagueType,
+    rosters: &[FantasyRoster],
+) -> Vec<TrendingPlayer> {
+    trending
+        .iter()
+        .filter_map(|t| {
+            let player = sleeper_players.player(&t.player_id)?;
+            // ESPN leagues have their own player IDs
+            let league_id = match league_type {
+                LeagueType::SLEEPER => Some(t.player_id.clone()),
+                LeagueType::ESPN => sleeper_players.espn_id(&t.player_id),
+            };
+            let availability = match league_id {
+                Some(id) => match players::rostered_by(&id, rosters) {
+                    Some(team) => Availability::Rostered(team.clone()),
+                    None => Availability::Available,
+                },
+                None => Availability::Unknown,
+            };
+            Some(TrendingPlayer {
+                player,
+                count: t.count,
+                availability,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trending(player_id: &str) -> SleeperTrendingPlayer {
+        SleeperTrendingPlayer {
+            player_id: player_id.to_string(),
+            count: 100,
+        }
+    }
+
+    #[test]
+    fn availability_by_league_type() {
+        let sleeper_players = SleeperPlayers::from_json(serde_json::json!({
+            "4034": {
+                "player_id": "4034", "first_name": "Christian", "last_name": "McCaffrey",
+                "position": "RB", "espn_id": 3117251
+            },
+            "6794": {
+                "player_id": "6794", "first_name": "Justin", "last_name": "Jefferson",
+                "position": "WR", "espn_id": "4262921"
+            },
+            "9999": {
+                "player_id": "9999", "first_name": "Some", "last_name": "Rookie",
+                "position": "WR"
+            }
+        }));
+        let roster = |player_id: &str| FantasyRoster {
+            team: FantasyTeam::test("1"),
+            starters: vec![FantasyPlayer {
+                id: player_id.to_string(),
+                name: player_id.to_string(),
+                position: "RB".to_string(),
+                nfl_team: None,
+                injury_status: None,
+            }],
+            bench: vec![],
+            reserve: vec![],
+            taxi: vec![],
+        };
+        let trending = vec![trending("4034"), trending("6794"), trending("9999")];
+        let availability = |players: &[TrendingPlayer]| -> Vec<String> {
+            players
+                .iter()
+                .map(|t| match &t.availability {
+                    Availability::Available => "available".to_string(),
+                    Availability::Rostered(team) => team.team_name.clone(),
+                    Availability::Unknown => "unknown".to_string(),
+                })
+                .collect()
+        };
+
+        // Sleeper leagues use Sleeper's IDs
+        let players = build(
+            &trending,
+            &sleeper_players,
+            &LeagueType::SLEEPER,
+            &[roster("4034")],
+        );
+        assert_eq!(players[0].player.name, "Christian McCaffrey");
+        assert_eq!(availability(&players), ["Team 1", "available", "available"]);
+
+        // ESPN leagues go through the mapping, whichever way it's written, and a player without
+        // one isn't called available
+        let players = build(
+            &trending,
+            &sleeper_players,
+            &LeagueType::ESPN,
+            &[roster("3117251")],
+        );
+        assert_eq!(availability(&players), ["Team 1", "available", "unknown"]);
+        let players = build(
+            &trending,
+            &sleeper_players,
+            &LeagueType::ESPN,
+            &[roster("4262921")],
+        );
+        assert_eq!(availability(&players), ["available", "Team 1", "unknown"]);
+    }
+}
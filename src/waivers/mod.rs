@@ -0,0 +1,90 @@
+use super::fantasy_client::PlayerProjection;
+
+// how many free agents to list
+pub const WAIVER_LIMIT: usize = 15;
+
+// Accepts the usual ways of writing a position, e.g. "d/st" for a team defense.
+pub fn normalize_position(position: &str) -> String {
+    match position.trim().to_uppercase().as_str() {
+        "D/ST" | "DST" | "D" => "DEF".to_string(),
+        "PK" => "K".to_string(),
+        p => p.to_string(),
+    }
+}
+
+// What a player should be worth per week from here on, blending how they've been scoring
+// lately with their rest-of-season projection.
+pub fn weekly_value(player: &PlayerProjection, weeks_left: u32) -> Option<f64> {
+    let rest_of_season = player.rest_of_season.map(|p| p / weeks_left.max(1) as f64);
+    match (player.recent_average(), rest_of_season) {
+        (Some(recent), Some(ros)) => Some((recent + ros) / 2.0),
+        (recent, ros) => recent.or(ros),
+    }
+}
+
+// The best free agents, optionally at just one position, with their weekly value.
+pub fn rank(
+    free_agents: Vec<PlayerProjection>,
+    position: Option<&str>,
+    weeks_left: u32,
+) -> Vec<(PlayerProjection, f64)> {
+    let position = position.map(normalize_position);
+    let mut ranked: Vec<(PlayerProjection, f64)> = free_agents
+        .into_iter()
+        .filter(|p| {
+            position
+                .as_ref()
+                .is_none_or(|pos| &p.player.position == pos)
+        })
+        .filter_map(|p| {
+            let value = weekly_value(&p, weeks_left)?;
+            Some((p, value))
+        })
+        .collect();
+    ranked.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
+    ranked.truncate(WAIVER_LIMIT);
+    ranked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fantasy_client::FantasyPlayer;
+
+    fn free_agent(
+        name: &str,
+        position: &str,
+        recent: Vec<f64>,
+        ros: Option<f64>,
+    ) -> PlayerProjection {
+        PlayerProjection {
+            player: FantasyPlayer {
+                id: name.to_string(),
+                name: name.to_string(),
+                position: position.to_string(),
+                nfl_team: None,
+                injury_status: None,
+            },
+            projected: None,
+            recent,
+            rest_of_season: ros,
+            season_points: None,
+        }
+    }
+
+    #[test]
+    fn ranks_by_recent_and_rest_of_season() {
+        let free_agents = vec![
+            free_agent("Flash", "WR", vec![20.0], Some(20.0)),
+            free_agent("Steady", "WR", vec![9.0, 11.0], Some(100.0)),
+            free_agent("Nobody", "WR", vec![], None),
+            free_agent("Kicker", "K", vec![8.0], Some(80.0)),
+        ];
+        let ranked = rank(free_agents, Some("wr"), 10);
+        let names: Vec<&str> = ranked.iter().map(|(p, _)| p.player.name.as_str()).collect();
+        assert_eq!(names, vec!["Flash", "Steady"]);
+        assert_eq!(ranked[0].1, 11.0);
+        assert_eq!(ranked[1].1, 10.0);
+        assert_eq!(normalize_position("d/st"), "DEF");
+    }
+}